@@ -9,8 +9,10 @@ import argparse
 # usage: plot.py [-h] [-x XLABEL] [-t TITLE] [-o OUTPUT] file
 # 
 # positional arguments:
-#   file                  space-separated data file; the first column is the parameter;
-#                         the next columns are the throughput in msg/s for:
+#   file                  space-separated data file; only the first table is read, the
+#                         following tables holding secondary metrics; the first column
+#                         is the parameter; the next columns are the throughput in
+#                         msg/s for:
 #                           - async-channel::bounded
 #                           - flume::bounded
 #                           - futures::mpsc
@@ -67,8 +69,9 @@ def plot(data, x_label, title, output):
 if __name__ == '__main__':
     parser = argparse.ArgumentParser()
     parser.add_argument("file", help="""
-space-separated data file; the first column is the
-parameter; the next columns are the throughput in msg/s for:
+space-separated data file; only the first table is read;
+the first column is the parameter; the next columns are the
+throughput in msg/s for:
 async-channel::bounded, flume::bounded, futures::mpsc,
 postage::mpsc, tachyonix, thingbuf, tokio::mpsc""")
     parser.add_argument("-x", "--xlabel", help="label of the x axis")
@@ -76,7 +79,16 @@ postage::mpsc, tachyonix, thingbuf, tokio::mpsc""")
     parser.add_argument("-o", "--output", help="name of the file to which the PNG plot should be saved")
     args = parser.parse_args()
 
+    # Read the throughput table, which is the first one, and ignore the
+    # tables of secondary metrics that may follow it.
     with open(args.file) as f:
-        data = numpy.loadtxt(f)
+        lines = []
+        for line in f:
+            if line.strip() == '':
+                if lines:
+                    break
+            elif not line.startswith('#'):
+                lines.append(line)
+        data = numpy.loadtxt(lines, ndmin=2)
         plot(data, args.xlabel, args.title, args.output)
 
@@ -0,0 +1,143 @@
+//! Pinning of executor worker threads to CPU cores.
+//!
+//! Pinning is only supported on Linux. When it is enabled, the executors that
+//! support it start one worker thread per selected core and pin each worker
+//! to a distinct core.
+
+use std::sync::OnceLock;
+
+static PINNED_CORES: OnceLock<Vec<usize>> = OnceLock::new();
+
+/// Enables the pinning of worker threads to the specified cores, or to all
+/// cores available to the process if no core is specified.
+pub fn enable(cores: Option<Vec<usize>>) -> Result<&'static [usize], String> {
+    let available_cores = available_cores()?;
+    let cores = match cores {
+        Some(cores) => {
+            if let Some(core) = cores.iter().find(|core| !available_cores.contains(core)) {
+                return Err(format!("Core {core} is not available to this process"));
+            }
+            cores
+        }
+        None => available_cores,
+    };
+
+    Ok(PINNED_CORES.get_or_init(|| cores))
+}
+
+/// Returns the cores to which worker threads are pinned, if pinning is
+/// enabled.
+pub fn pinned_cores() -> Option<&'static [usize]> {
+    PINNED_CORES.get().map(Vec::as_slice)
+}
+
+/// Parses a comma-separated list of cores and core ranges such as `0,2,4-7`.
+pub fn parse_core_list(list: &str) -> Result<Vec<usize>, String> {
+    let mut cores = Vec::new();
+    for item in list.split(',') {
+        let parse = |core: &str| {
+            core.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid core list '{list}'"))
+        };
+        match item.split_once('-') {
+            Some((first, last)) => cores.extend(parse(first)?..=parse(last)?),
+            None => cores.push(parse(item)?),
+        }
+    }
+    if cores.is_empty() {
+        return Err(format!("Invalid core list '{list}'"));
+    }
+    for (i, core) in cores.iter().enumerate() {
+        if cores[..i].contains(core) {
+            return Err(format!("Core {core} is listed more than once"));
+        }
+    }
+
+    Ok(cores)
+}
+
+/// Pins the calling thread to the specified core.
+pub fn pin_current_thread(core: usize) {
+    pin_thread(0, core);
+}
+
+/// Pins the worker threads created since `previous_threads` was recorded with
+/// [`thread_ids`], in the order given by the numeric suffix of their names.
+///
+/// This is meant for executors that do not provide a thread start hook.
+pub fn pin_new_threads(previous_threads: &[u32], cores: &[usize]) {
+    let mut new_threads: Vec<_> = thread_ids()
+        .into_iter()
+        .filter(|id| !previous_threads.contains(id))
+        .map(|id| {
+            let name =
+                std::fs::read_to_string(format!("/proc/self/task/{id}/comm")).unwrap_or_default();
+            let index = name
+                .trim_end()
+                .rsplit(|c: char| !c.is_ascii_digit())
+                .next()
+                .and_then(|index| index.parse::<usize>().ok())
+                .unwrap_or(usize::MAX);
+
+            (index, id)
+        })
+        .collect();
+    new_threads.sort();
+
+    for (i, (_, id)) in new_threads.into_iter().enumerate() {
+        pin_thread(id, cores[i % cores.len()]);
+    }
+}
+
+/// Returns the kernel IDs of the threads of the process.
+pub fn thread_ids() -> Vec<u32> {
+    std::fs::read_dir("/proc/self/task")
+        .map(|tasks| {
+            tasks
+                .filter_map(|task| task.ok()?.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn available_cores() -> Result<Vec<usize>, String> {
+    // Safety: `cpu_set_t` is a plain bit mask and the size passed to the
+    // kernel is that of the mask.
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(String::from(
+                "Could not read the CPU affinity of the process",
+            ));
+        }
+
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|&core| libc::CPU_ISSET(core, &set))
+            .collect())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_cores() -> Result<Vec<usize>, String> {
+    Err(String::from("Thread pinning is only supported on Linux"))
+}
+
+#[cfg(target_os = "linux")]
+fn pin_thread(thread_id: u32, core: usize) {
+    // Safety: see `available_cores`.
+    let result = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(
+            thread_id as libc::pid_t,
+            std::mem::size_of::<libc::cpu_set_t>(),
+            &set,
+        )
+    };
+    assert!(result == 0, "could not pin a worker thread to core {core}");
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_thread(_thread_id: u32, _core: usize) {}
@@ -1,2 +1,16 @@
+pub mod backpressure;
+pub mod burst;
+pub mod executor;
 pub mod funnel;
+pub mod funnel_try;
+pub mod oneshot;
 pub mod pinball;
+pub mod rpc;
+pub mod teardown;
+pub mod watch;
+
+pub use funnel::payload as funnel_payload;
+pub use funnel::unbounded as funnel_unbounded;
+pub use pinball::payload as pinball_payload;
+pub use pinball::routing as pinball_routing;
+pub use pinball::unbounded as pinball_unbounded;
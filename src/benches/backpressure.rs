@@ -0,0 +1,99 @@
+macro_rules! bench {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::sync::{Arc, Mutex};
+            use std::time::Instant;
+
+            use crate::channel_shims::$channel_name::channel;
+            use crate::executor_shims::Executor;
+            use crate::utils::spin_for;
+            use crate::{BenchConfig, BenchIterator, BenchResult};
+
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                let samples = config.samples;
+                const MESSAGES_PER_CHANNEL: usize = 100_000;
+                const CHANNELS: usize = 61;
+                const SENDERS_PER_CHANNEL: usize = 13;
+                const CAPACITY: usize = 10;
+                let messages_per_sender = MESSAGES_PER_CHANNEL / SENDERS_PER_CHANNEL;
+                let total_messages = messages_per_sender * SENDERS_PER_CHANNEL * CHANNELS;
+
+                let results = [0, 100, 300, 1000].into_iter().map(move |work: u64| {
+                    // Calibrate the spin loop outside of the timed region.
+                    spin_for(work);
+
+                    let (throughput, fairness): (Vec<_>, Vec<_>) = (0..samples.get())
+                        .map(|_| {
+                            let mut executor = E::default();
+                            let fairness = Arc::new(Mutex::new(Vec::new()));
+
+                            for _ in 0..CHANNELS {
+                                let (s, mut r) = channel(CAPACITY);
+
+                                for sender_id in 0..SENDERS_PER_CHANNEL {
+                                    let mut s = s.clone();
+
+                                    executor.spawn(async move {
+                                        for _ in 0..messages_per_sender {
+                                            s.send(sender_id).await;
+                                        }
+                                    });
+                                }
+
+                                let fairness = fairness.clone();
+                                executor.spawn(async move {
+                                    // Fairness is only assessed over the
+                                    // window where no sender can have
+                                    // completed yet.
+                                    let mut counts = [0usize; SENDERS_PER_CHANNEL];
+                                    for _ in 0..messages_per_sender {
+                                        let sender_id = r.recv().await.unwrap();
+                                        counts[sender_id] += 1;
+                                        spin_for(work);
+                                    }
+                                    for _ in messages_per_sender
+                                        ..messages_per_sender * SENDERS_PER_CHANNEL
+                                    {
+                                        r.recv().await.unwrap();
+                                        spin_for(work);
+                                    }
+
+                                    fairness.lock().unwrap().push(jain_index(&counts));
+                                })
+                            }
+
+                            let start_time = Instant::now();
+                            executor.join_all();
+                            let duration = Instant::now() - start_time;
+
+                            let fairness = fairness.lock().unwrap();
+                            let mean_fairness =
+                                fairness.iter().sum::<f64>() / fairness.len() as f64;
+
+                            (
+                                total_messages as f64 / duration.as_secs_f64(),
+                                mean_fairness,
+                            )
+                        })
+                        .unzip();
+
+                    BenchResult::new(String::from("work [ns]"), work.to_string(), throughput)
+                        .with_metric("fairness", "Jain", fairness)
+                });
+
+                Box::new(results)
+            }
+
+            /// Jain's fairness index: 1 if all senders were served equally,
+            /// 1/n if a single sender was served.
+            fn jain_index(counts: &[usize]) -> f64 {
+                let sum: f64 = counts.iter().map(|&c| c as f64).sum();
+                let sum_sq: f64 = counts.iter().map(|&c| (c * c) as f64).sum();
+
+                sum * sum / (counts.len() as f64 * sum_sq)
+            }
+        }
+    };
+}
+
+crate::macros::add_bench!();
@@ -0,0 +1,81 @@
+macro_rules! bench {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::time::Instant;
+
+            use crate::channel_shims::$channel_name::channel;
+            use crate::executor_shims::Executor;
+            use crate::utils::yield_now;
+            use crate::{BenchConfig, BenchIterator, BenchResult};
+
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                let samples = config.samples;
+                const MESSAGES_PER_CHANNEL: usize = 1_000_000;
+                const CHANNELS: usize = 61;
+                const SENDERS_PER_CHANNEL: usize = 13;
+                const IDLE_YIELDS: usize = 10;
+
+                let results = [1, 10, 100, 1000]
+                    .into_iter()
+                    .map(move |burst_size: usize| {
+                        let bursts_per_sender =
+                            MESSAGES_PER_CHANNEL / SENDERS_PER_CHANNEL / burst_size;
+                        let messages_per_sender = bursts_per_sender * burst_size;
+                        let total_messages = messages_per_sender * SENDERS_PER_CHANNEL * CHANNELS;
+
+                        let throughput: Vec<_> = (0..samples.get())
+                            .map(|_| {
+                                let mut executor = E::default();
+
+                                for _ in 0..CHANNELS {
+                                    // Make sure that a full burst from each
+                                    // sender fits in the channel so that
+                                    // senders never block.
+                                    let (s, mut r) = channel(burst_size * SENDERS_PER_CHANNEL);
+
+                                    for _ in 0..SENDERS_PER_CHANNEL {
+                                        let mut s = s.clone();
+
+                                        executor.spawn(async move {
+                                            for _ in 0..bursts_per_sender {
+                                                for i in 0..burst_size {
+                                                    s.send(i).await;
+                                                }
+
+                                                // Idle period between bursts.
+                                                for _ in 0..IDLE_YIELDS {
+                                                    yield_now().await;
+                                                }
+                                            }
+                                        });
+                                    }
+
+                                    executor.spawn(async move {
+                                        for _ in 0..messages_per_sender * SENDERS_PER_CHANNEL {
+                                            r.recv().await.unwrap();
+                                        }
+                                    })
+                                }
+
+                                let start_time = Instant::now();
+                                executor.join_all();
+                                let duration = Instant::now() - start_time;
+
+                                total_messages as f64 / duration.as_secs_f64()
+                            })
+                            .collect();
+
+                        BenchResult::new(
+                            String::from("burst size"),
+                            burst_size.to_string(),
+                            throughput,
+                        )
+                    });
+
+                Box::new(results)
+            }
+        }
+    };
+}
+
+crate::macros::add_bench!();
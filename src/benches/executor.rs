@@ -0,0 +1,207 @@
+//! Benches that only exercise executors, without any channel.
+//!
+//! All benches are parametrized by the number of concurrent tasks and process
+//! the same total number of tasks or wake-ups regardless of this number.
+
+/// Numbers of concurrent tasks.
+const TASK_COUNTS: [usize; 5] = [2, 16, 128, 1024, 8192];
+
+/// Number of tasks or wake-ups processed by each sample.
+const TOTAL: usize = 1 << 20;
+
+/// Spawns tasks that complete immediately and joins them, measuring the
+/// spawning and the joining together.
+pub mod fan_out {
+    use std::time::Instant;
+
+    use crate::executor_shims::Executor;
+    use crate::{BenchConfig, BenchIterator, BenchResult};
+
+    use super::{TASK_COUNTS, TOTAL};
+
+    pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+        let samples = config.samples;
+
+        let results = TASK_COUNTS.into_iter().map(move |task_count| {
+            let rounds = TOTAL / task_count;
+
+            let throughput: Vec<_> = (0..samples.get())
+                .map(|_| {
+                    let mut executor = E::default();
+
+                    let start_time = Instant::now();
+                    for _ in 0..rounds {
+                        for i in 0..task_count {
+                            executor.spawn(async move {
+                                std::hint::black_box(i);
+                            });
+                        }
+                        executor.join_all();
+                    }
+                    let duration = Instant::now() - start_time;
+
+                    (rounds * task_count) as f64 / duration.as_secs_f64()
+                })
+                .collect();
+
+            BenchResult::new(
+                String::from("task count"),
+                task_count.to_string(),
+                throughput,
+            )
+            .with_unit("task")
+        });
+
+        Box::new(results)
+    }
+}
+
+/// Joins tasks that complete immediately once released, measuring only the
+/// joining.
+pub mod join {
+    use std::time::{Duration, Instant};
+
+    use crate::executor_shims::Executor;
+    use crate::utils::StartGate;
+    use crate::{BenchConfig, BenchIterator, BenchResult};
+
+    use super::{TASK_COUNTS, TOTAL};
+
+    pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+        let samples = config.samples;
+
+        let results = TASK_COUNTS.into_iter().map(move |task_count| {
+            let rounds = TOTAL / task_count;
+
+            let throughput: Vec<_> = (0..samples.get())
+                .map(|_| {
+                    let mut executor = E::default();
+
+                    let mut duration = Duration::ZERO;
+                    for _ in 0..rounds {
+                        // Hold the tasks until the timed region since some
+                        // executors run them as soon as they are spawned.
+                        let gate = StartGate::default();
+                        for i in 0..task_count {
+                            let gate = gate.clone();
+                            executor.spawn(async move {
+                                gate.wait().await;
+                                std::hint::black_box(i);
+                            });
+                        }
+
+                        let start_time = Instant::now();
+                        gate.open();
+                        executor.join_all();
+                        duration += Instant::now() - start_time;
+                    }
+
+                    (rounds * task_count) as f64 / duration.as_secs_f64()
+                })
+                .collect();
+
+            BenchResult::new(
+                String::from("task count"),
+                task_count.to_string(),
+                throughput,
+            )
+            .with_unit("task")
+        });
+
+        Box::new(results)
+    }
+}
+
+/// Pairs of tasks that take turns by waking each other with their `Waker`.
+pub mod yield_ping {
+    use std::future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Poll, Waker};
+    use std::time::Instant;
+
+    use crate::executor_shims::Executor;
+    use crate::utils::StartGate;
+    use crate::{BenchConfig, BenchIterator, BenchResult};
+
+    use super::{TASK_COUNTS, TOTAL};
+
+    /// The state shared by the two players of a pair.
+    #[derive(Default)]
+    struct Table {
+        /// The number of turns played so far; player `p` plays when the
+        /// parity of the turn is `p`.
+        turn: AtomicUsize,
+        wakers: [Mutex<Option<Waker>>; 2],
+    }
+
+    /// Plays the specified number of turns, waking the other player after
+    /// each turn.
+    async fn play(table: Arc<Table>, player: usize, turns: usize, gate: StartGate) {
+        gate.wait().await;
+
+        for k in 0..turns {
+            let turn = 2 * k + player;
+
+            future::poll_fn(|cx| {
+                if table.turn.load(Ordering::Acquire) == turn {
+                    return Poll::Ready(());
+                }
+                *table.wakers[player].lock().unwrap() = Some(cx.waker().clone());
+
+                // Check again in case the other player completed its turn
+                // before the waker was registered, in which case the waker
+                // is withdrawn to avoid a spurious wake-up.
+                if table.turn.load(Ordering::Acquire) == turn {
+                    table.wakers[player].lock().unwrap().take();
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await;
+
+            table.turn.store(turn + 1, Ordering::Release);
+            if let Some(waker) = table.wakers[1 - player].lock().unwrap().take() {
+                waker.wake();
+            }
+        }
+    }
+
+    pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+        let samples = config.samples;
+
+        let results = TASK_COUNTS.into_iter().map(move |task_count| {
+            let turns_per_task = TOTAL / task_count;
+
+            let throughput: Vec<_> = (0..samples.get())
+                .map(|_| {
+                    let mut executor = E::default();
+                    let gate = StartGate::default();
+
+                    for _ in 0..task_count / 2 {
+                        let table = Arc::new(Table::default());
+                        executor.spawn(play(table.clone(), 0, turns_per_task, gate.clone()));
+                        executor.spawn(play(table, 1, turns_per_task, gate.clone()));
+                    }
+
+                    let start_time = Instant::now();
+                    gate.open();
+                    executor.join_all();
+                    let duration = Instant::now() - start_time;
+
+                    (turns_per_task * task_count) as f64 / duration.as_secs_f64()
+                })
+                .collect();
+
+            BenchResult::new(
+                String::from("task count"),
+                task_count.to_string(),
+                throughput,
+            )
+            .with_unit("turn")
+        });
+
+        Box::new(results)
+    }
+}
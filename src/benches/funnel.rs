@@ -1,58 +1,184 @@
+/// The dimensions of a funnel topology.
+pub struct Funnel {
+    pub channels: usize,
+    pub senders_per_channel: usize,
+    pub messages_per_sender: usize,
+}
+
+/// Spawns the tasks of a funnel topology with a given channel constructor
+/// expression and message type and returns the time spent spawning them.
+///
+/// The tasks wait for the start gate to open. If a monitor is specified, each
+/// task reports its state with a probe.
+macro_rules! spawn_funnel {
+    (
+        $executor:expr,
+        $funnel:expr,
+        $channel:expr,
+        $payload:ty,
+        $work:expr,
+        $gate:expr,
+        $violations:expr,
+        $monitor:expr
+    ) => {{
+        use crate::watchdog::TaskState;
+
+        let executor = $executor;
+        let funnel: &crate::benches::funnel::Funnel = $funnel;
+        let messages_per_sender = funnel.messages_per_sender;
+        let senders_per_channel = funnel.senders_per_channel;
+        let work: u64 = $work;
+        let gate: &crate::utils::StartGate = $gate;
+        let violations: &crate::validation::Violations = $violations;
+        let monitor: Option<&crate::watchdog::Monitor> = $monitor;
+        let mut spawn_time = Duration::ZERO;
+
+        for channel_id in 0..funnel.channels {
+            let (s, mut r) = $channel;
+
+            for sender_id in 0..senders_per_channel {
+                let mut s = s.clone();
+                let gate = gate.clone();
+                let probe = monitor
+                    .map(|monitor| {
+                        monitor.probe(format!("funnel channel {channel_id} sender {sender_id}"))
+                    })
+                    .unwrap_or_default();
+
+                let spawn_start_time = Instant::now();
+                executor.spawn(async move {
+                    gate.wait().await;
+                    for i in 0..messages_per_sender {
+                        let mut message = <$payload as Payload>::new(i);
+                        message.set_tag(sender_id, i);
+                        probe.set(TaskState::Sending);
+                        s.send(message).await;
+                        probe.advance();
+                    }
+                });
+                spawn_time += Instant::now() - spawn_start_time;
+            }
+
+            let mut checker = <$payload as Payload>::TAGGED.then(|| {
+                crate::validation::SequenceChecker::new(
+                    channel_id,
+                    senders_per_channel,
+                    violations.clone(),
+                )
+            });
+            let gate = gate.clone();
+            let probe = monitor
+                .map(|monitor| monitor.probe(format!("funnel channel {channel_id} receiver")))
+                .unwrap_or_default();
+
+            let spawn_start_time = Instant::now();
+            executor.spawn(async move {
+                gate.wait().await;
+                for _ in 0..messages_per_sender * senders_per_channel {
+                    probe.set(TaskState::Receiving);
+                    let Some(message) = r.recv().await else {
+                        break;
+                    };
+                    probe.advance();
+                    if let (Some(checker), Some((sender_id, seq))) = (&mut checker, message.tag()) {
+                        checker.check(sender_id, seq);
+                    }
+                    crate::utils::spin_for(work);
+                }
+                if let Some(checker) = checker {
+                    checker.finish(&vec![messages_per_sender; senders_per_channel]);
+                }
+            });
+            spawn_time += Instant::now() - spawn_start_time;
+        }
+
+        spawn_time
+    }};
+}
+
+pub(crate) use spawn_funnel;
+
+/// Runs all samples for a given configuration, channel constructor expression
+/// and message type and returns the measured throughputs, spawn times and
+/// resource usage.
+macro_rules! funnel {
+    ($executor:ty, $config:expr, $channel:expr, $payload:ty) => {{
+        const MESSAGES_PER_CHANNEL: usize = 1_000_000;
+        const CHANNELS: usize = 61;
+        const SENDERS_PER_CHANNEL: usize = 13;
+        let funnel = crate::benches::funnel::Funnel {
+            channels: CHANNELS,
+            senders_per_channel: SENDERS_PER_CHANNEL,
+            messages_per_sender: MESSAGES_PER_CHANNEL / SENDERS_PER_CHANNEL,
+        };
+        let total_messages = funnel.messages_per_sender * SENDERS_PER_CHANNEL * CHANNELS;
+        let config: &crate::BenchConfig = $config;
+        let work = config.work;
+
+        // Calibrate the spin loop outside of the timed region.
+        crate::utils::spin_for(work);
+
+        let mut throughput = Vec::new();
+        let mut spawn_times = Vec::new();
+        let mut measurements = Vec::new();
+
+        for _ in 0..config.samples.get() {
+            let mut executor = <$executor>::default();
+            let violations = crate::validation::Violations::default();
+            let gate = crate::utils::StartGate::default();
+
+            let spawn_time = crate::benches::funnel::spawn_funnel!(
+                &mut executor,
+                &funnel,
+                $channel,
+                $payload,
+                work,
+                &gate,
+                &violations,
+                None
+            );
+
+            let meter = crate::rusage::Meter::start();
+            let start_time = Instant::now();
+            gate.open();
+            executor.join_all();
+            let duration = Instant::now() - start_time;
+            let measurement = meter.stop();
+
+            violations.assert_none();
+
+            throughput.push(total_messages as f64 / duration.as_secs_f64());
+            spawn_times.push(spawn_time.as_secs_f64() * 1e6);
+            measurements.push(measurement);
+        }
+
+        (throughput, spawn_times, measurements)
+    }};
+}
+
 macro_rules! bench {
     ($channel_name:ident) => {
         pub mod $channel_name {
-            use std::num::NonZeroU32;
-            use std::time::Instant;
+            use std::time::{Duration, Instant};
 
             use crate::channel_shims::$channel_name::channel;
             use crate::executor_shims::Executor;
-            use crate::{BenchIterator, BenchResult};
-
-            pub fn bench<E: Executor>(samples: NonZeroU32) -> BenchIterator {
-                const MESSAGES_PER_CHANNEL: usize = 1_000_000;
-                const CHANNELS: usize = 61;
-                const SENDERS_PER_CHANNEL: usize = 13;
-                let total_messages =
-                    (MESSAGES_PER_CHANNEL / SENDERS_PER_CHANNEL) * SENDERS_PER_CHANNEL * CHANNELS;
+            use crate::payload::{Payload, Tagged};
+            use crate::{BenchConfig, BenchIterator, BenchResult};
 
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
                 let results = [1, 10, 100, 1000, 10000]
                     .into_iter()
                     .map(move |capacity: usize| {
-                        let throughput: Vec<_> = (0..samples.get())
-                            .map(|_| {
-                                let mut executor = E::default();
-
-                                for _ in 0..CHANNELS {
-                                    let (s, mut r) = channel(capacity);
-
-                                    for _ in 0..SENDERS_PER_CHANNEL {
-                                        let mut s = s.clone();
-
-                                        let _ = executor.spawn(async move {
-                                            for i in 0..MESSAGES_PER_CHANNEL / SENDERS_PER_CHANNEL {
-                                                s.send(i).await;
-                                            }
-                                        });
-                                    }
-
-                                    executor.spawn(async move {
-                                        for _ in 0..(MESSAGES_PER_CHANNEL / SENDERS_PER_CHANNEL)
-                                            * SENDERS_PER_CHANNEL
-                                        {
-                                            r.recv().await.unwrap();
-                                        }
-                                    })
-                                }
-
-                                let start_time = Instant::now();
-                                executor.join_all();
-                                let duration = Instant::now() - start_time;
-
-                                total_messages as f64 / duration.as_secs_f64()
-                            })
-                            .collect();
+                        let (throughput, spawn_time, usage) = if config.validate {
+                            funnel!(E, &config, channel(capacity), Tagged)
+                        } else {
+                            funnel!(E, &config, channel(capacity), usize)
+                        };
 
                         BenchResult::new(String::from("capacity"), capacity.to_string(), throughput)
+                            .with_metric("spawn time", "µs", spawn_time)
+                            .with_resource_usage(usage)
                     });
 
                 Box::new(results)
@@ -62,3 +188,95 @@ macro_rules! bench {
 }
 
 crate::macros::add_bench!();
+
+/// Funnel benchmark with unbounded channels.
+pub mod unbounded {
+    macro_rules! bench {
+        ($channel_name:ident) => {
+            pub mod $channel_name {
+                use std::time::{Duration, Instant};
+
+                use crate::channel_shims::$channel_name::unbounded_channel;
+                use crate::executor_shims::Executor;
+                use crate::payload::{Payload, Tagged};
+                use crate::{BenchConfig, BenchIterator, BenchResult};
+
+                pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                    let results = std::iter::once_with(move || {
+                        let (throughput, spawn_time, usage) = if config.validate {
+                            funnel!(E, &config, unbounded_channel(), Tagged)
+                        } else {
+                            funnel!(E, &config, unbounded_channel(), usize)
+                        };
+
+                        BenchResult::new(
+                            String::from("capacity"),
+                            String::from("unbounded"),
+                            throughput,
+                        )
+                        .with_metric("spawn time", "µs", spawn_time)
+                        .with_resource_usage(usage)
+                    });
+
+                    Box::new(results)
+                }
+            }
+        };
+    }
+
+    crate::macros::add_unbounded_bench!();
+}
+
+/// Funnel benchmark with various message types.
+pub mod payload {
+    macro_rules! bench {
+        ($channel_name:ident) => {
+            pub mod $channel_name {
+                use std::time::{Duration, Instant};
+
+                use crate::channel_shims::$channel_name::channel;
+                use crate::executor_shims::Executor;
+                use crate::payload::{Boxed, Inline, Payload};
+                use crate::rusage::Measurement;
+                use crate::{BenchConfig, BenchIterator, BenchResult};
+
+                pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                    const CAPACITY: usize = 100;
+
+                    let payloads: [(
+                        &str,
+                        fn(&BenchConfig) -> (Vec<f64>, Vec<f64>, Vec<Option<Measurement>>),
+                    ); 5] = [
+                        ("8", |config| {
+                            funnel!(E, config, channel(CAPACITY), Inline<8>)
+                        }),
+                        ("64", |config| {
+                            funnel!(E, config, channel(CAPACITY), Inline<64>)
+                        }),
+                        ("256", |config| {
+                            funnel!(E, config, channel(CAPACITY), Inline<256>)
+                        }),
+                        ("1024", |config| {
+                            funnel!(E, config, channel(CAPACITY), Inline<1024>)
+                        }),
+                        ("boxed_1024", |config| {
+                            funnel!(E, config, channel(CAPACITY), Boxed<1024>)
+                        }),
+                    ];
+
+                    let results = payloads.into_iter().map(move |(size, run)| {
+                        let (throughput, spawn_time, usage) = run(&config);
+
+                        BenchResult::new(String::from("payload"), size.to_string(), throughput)
+                            .with_metric("spawn time", "µs", spawn_time)
+                            .with_resource_usage(usage)
+                    });
+
+                    Box::new(results)
+                }
+            }
+        };
+    }
+
+    crate::macros::add_bench!();
+}
@@ -0,0 +1,181 @@
+/// Runs all samples for a given channel constructor expression and returns the
+/// measured throughputs, spawn times, resource usage and `try_send` and
+/// `try_recv` success percentages.
+macro_rules! funnel_try {
+    ($executor:ty, $config:expr, $channel:expr) => {{
+        const MESSAGES_PER_CHANNEL: usize = 1_000_000;
+        const CHANNELS: usize = 61;
+        const SENDERS_PER_CHANNEL: usize = 13;
+        let messages_per_sender = MESSAGES_PER_CHANNEL / SENDERS_PER_CHANNEL;
+        let total_messages = messages_per_sender * SENDERS_PER_CHANNEL * CHANNELS;
+        let config: &crate::BenchConfig = $config;
+        let work = config.work;
+
+        // Calibrate the spin loop outside of the timed region.
+        crate::utils::spin_for(work);
+
+        let mut throughput = Vec::new();
+        let mut spawn_times = Vec::new();
+        let mut measurements = Vec::new();
+        let mut send_hits = Vec::new();
+        let mut recv_hits = Vec::new();
+
+        for _ in 0..config.samples.get() {
+            let mut executor = <$executor>::default();
+            let send_failures = Arc::new(AtomicUsize::new(0));
+            let recv_failures = Arc::new(AtomicUsize::new(0));
+            let gate = crate::utils::StartGate::default();
+            let mut spawn_time = Duration::ZERO;
+
+            for _ in 0..CHANNELS {
+                let (s, mut r) = $channel;
+
+                for _ in 0..SENDERS_PER_CHANNEL {
+                    let mut s = s.clone();
+                    let send_failures = send_failures.clone();
+                    let gate = gate.clone();
+
+                    let spawn_start_time = Instant::now();
+                    executor.spawn(async move {
+                        gate.wait().await;
+
+                        // Fall back to an async send when the channel is
+                        // full.
+                        let mut failures = 0;
+                        for i in 0..messages_per_sender {
+                            if let Err(message) = s.try_send(i) {
+                                failures += 1;
+                                s.send(message).await;
+                            }
+                        }
+                        send_failures.fetch_add(failures, Ordering::Relaxed);
+                    });
+                    spawn_time += Instant::now() - spawn_start_time;
+                }
+
+                let recv_failures = recv_failures.clone();
+                let gate = gate.clone();
+                let spawn_start_time = Instant::now();
+                executor.spawn(async move {
+                    gate.wait().await;
+
+                    // Fall back to an async receive when the channel is
+                    // empty.
+                    let mut failures = 0;
+                    for _ in 0..messages_per_sender * SENDERS_PER_CHANNEL {
+                        if r.try_recv().is_none() {
+                            failures += 1;
+                            r.recv().await.unwrap();
+                        }
+                        crate::utils::spin_for(work);
+                    }
+                    recv_failures.fetch_add(failures, Ordering::Relaxed);
+                });
+                spawn_time += Instant::now() - spawn_start_time;
+            }
+
+            let meter = crate::rusage::Meter::start();
+            let start_time = Instant::now();
+            gate.open();
+            executor.join_all();
+            let duration = Instant::now() - start_time;
+            let measurement = meter.stop();
+
+            let hit_ratio = |failures: &AtomicUsize| {
+                100.0 * (1.0 - failures.load(Ordering::Relaxed) as f64 / total_messages as f64)
+            };
+            throughput.push(total_messages as f64 / duration.as_secs_f64());
+            spawn_times.push(spawn_time.as_secs_f64() * 1e6);
+            measurements.push(measurement);
+            send_hits.push(hit_ratio(&send_failures));
+            recv_hits.push(hit_ratio(&recv_failures));
+        }
+
+        (throughput, spawn_times, measurements, send_hits, recv_hits)
+    }};
+}
+
+/// Returns a function running the bench with an unbounded channel, for the
+/// channels that support them.
+macro_rules! unbounded {
+    (async_channel, $executor:ty) => {
+        unbounded!(@supported async_channel, $executor)
+    };
+    (flume, $executor:ty) => {
+        unbounded!(@supported flume, $executor)
+    };
+    (futures_mpsc, $executor:ty) => {
+        unbounded!(@supported futures_mpsc, $executor)
+    };
+    (tokio_mpsc, $executor:ty) => {
+        unbounded!(@supported tokio_mpsc, $executor)
+    };
+    (@supported $channel_name:ident, $executor:ty) => {
+        Some(|config| {
+            funnel_try!(
+                $executor,
+                config,
+                crate::channel_shims::$channel_name::unbounded_channel()
+            )
+        })
+    };
+    ($channel_name:ident, $executor:ty) => {
+        None
+    };
+}
+
+macro_rules! bench {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            use std::sync::Arc;
+            use std::time::{Duration, Instant};
+
+            use crate::channel_shims::$channel_name::channel;
+            use crate::executor_shims::Executor;
+            use crate::rusage::Measurement;
+            use crate::{BenchConfig, BenchIterator, BenchResult};
+
+            /// The throughputs, spawn times, resource usage and `try_send` and
+            /// `try_recv` success percentages of all samples.
+            type Samples = (
+                Vec<f64>,
+                Vec<f64>,
+                Vec<Option<Measurement>>,
+                Vec<f64>,
+                Vec<f64>,
+            );
+
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                let unbounded_config = config.clone();
+                let unbounded: Option<fn(&BenchConfig) -> Samples> = unbounded!($channel_name, E);
+
+                let result =
+                    |capacity: String, (throughput, spawn_time, usage, send_hits, recv_hits)| {
+                        BenchResult::new(String::from("capacity"), capacity, throughput)
+                            .with_metric("spawn time", "µs", spawn_time)
+                            .with_metric("try_send success", "%", send_hits)
+                            .with_metric("try_recv success", "%", recv_hits)
+                            .with_resource_usage(usage)
+                    };
+
+                let results =
+                    [1, 10, 100, 1000, 10000]
+                        .into_iter()
+                        .map(move |capacity: usize| {
+                            result(
+                                capacity.to_string(),
+                                funnel_try!(E, &config, channel(capacity)),
+                            )
+                        })
+                        .chain(unbounded.into_iter().map(move |run| {
+                            result(String::from("unbounded"), run(&unbounded_config))
+                        }));
+
+                Box::new(results)
+            }
+        }
+    };
+}
+
+crate::macros::add_bench!();
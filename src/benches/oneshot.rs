@@ -0,0 +1,77 @@
+macro_rules! bench {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::time::Instant;
+
+            use crate::executor_shims::Executor;
+            use crate::oneshot_shims::$channel_name::{channel, Sender};
+            use crate::{BenchConfig, BenchIterator, BenchResult};
+
+            /// A message carrying the sender of the oneshot channel on which
+            /// the next message is expected.
+            struct Ball(Sender<Ball>);
+
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                let samples = config.samples;
+                const TOTAL_EXCHANGES: usize = 2_000_000;
+
+                let results = [1, 4, 16, 64, 256]
+                    .into_iter()
+                    .map(move |pair_count: usize| {
+                        // Each round trip involves 2 oneshot channels.
+                        let round_trips_per_pair = TOTAL_EXCHANGES / pair_count / 2;
+                        let total_messages = round_trips_per_pair * 2 * pair_count;
+
+                        let throughput: Vec<_> = (0..samples.get())
+                            .map(|_| {
+                                let mut executor = E::default();
+
+                                for _ in 0..pair_count {
+                                    // Each task creates a new oneshot channel at
+                                    // each exchange and sends its sender to the
+                                    // other task through the previous channel.
+                                    let (s, r) = channel();
+
+                                    executor.spawn(async move {
+                                        let mut s = s;
+                                        for _ in 0..round_trips_per_pair {
+                                            let (next_s, next_r) = channel();
+                                            s.send(Ball(next_s));
+                                            let Ball(reply_s) = next_r.recv().await.unwrap();
+                                            s = reply_s;
+                                        }
+                                    });
+
+                                    executor.spawn(async move {
+                                        let mut r = r;
+                                        for _ in 0..round_trips_per_pair {
+                                            let Ball(reply_s) = r.recv().await.unwrap();
+                                            let (next_s, next_r) = channel();
+                                            reply_s.send(Ball(next_s));
+                                            r = next_r;
+                                        }
+                                    });
+                                }
+
+                                let start_time = Instant::now();
+                                executor.join_all();
+                                let duration = Instant::now() - start_time;
+
+                                total_messages as f64 / duration.as_secs_f64()
+                            })
+                            .collect();
+
+                        BenchResult::new(
+                            String::from("pair count"),
+                            pair_count.to_string(),
+                            throughput,
+                        )
+                    });
+
+                Box::new(results)
+            }
+        }
+    };
+}
+
+crate::macros::add_oneshot_bench!();
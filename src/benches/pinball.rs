@@ -1,144 +1,329 @@
+use crate::graph::Graph;
+use crate::BenchConfig;
+
+/// Number of nodes of the default complete graph.
+pub const NODES_PER_GRAPH: usize = 13;
+
+/// Returns the graph specified in the configuration or, by default, a
+/// complete graph.
+pub fn graph_from_config(config: &BenchConfig) -> Graph {
+    config
+        .graph
+        .clone()
+        .unwrap_or_else(|| Graph::complete(NODES_PER_GRAPH))
+}
+
+/// The dimensions of a pinball topology.
+pub struct Pinball {
+    pub graph_count: usize,
+    pub graph: Graph,
+    pub visitor_count: usize,
+    /// The path length of each visitor.
+    pub path_length: usize,
+    /// The seed of the random walks.
+    pub seed: u64,
+}
+
+/// Spawns the tasks of a pinball topology with a given channel constructor
+/// expression and message type and returns the time spent spawning them
+/// together with the validators of the graphs.
+///
+/// The tasks wait for the start gate to open. If a monitor is specified, each
+/// task reports its state with a probe.
+macro_rules! spawn_pinball {
+    (
+        $executor:expr,
+        $pinball:expr,
+        $channel:expr,
+        $payload:ty,
+        $work:expr,
+        $gate:expr,
+        $violations:expr,
+        $monitor:expr
+    ) => {{
+        use crate::watchdog::TaskState;
+
+        let executor = $executor;
+        let pinball: &crate::benches::pinball::Pinball = $pinball;
+        let nodes_per_graph = pinball.graph.node_count();
+        let visitor_count = pinball.visitor_count;
+        let total_visitor_path_length = pinball.path_length;
+        let work: u64 = $work;
+        let gate: &crate::utils::StartGate = $gate;
+        let violations: &crate::validation::Violations = $violations;
+        let monitor: Option<&crate::watchdog::Monitor> = $monitor;
+        let mut graph_validators = Vec::new();
+        let mut spawn_time = Duration::ZERO;
+
+        for graph_id in 0..pinball.graph_count {
+            let mut senders = Vec::new();
+            let mut receivers = Vec::new();
+
+            // Build a sender-receiver pair for each graph
+            // node.
+            for _ in 0..nodes_per_graph {
+                let (s, r) = $channel;
+                senders.push(s);
+                receivers.push(r);
+            }
+
+            // Count how many visitors have completed their
+            // journey through the graph.
+            let halted_visitors = Arc::new(AtomicUsize::new(0));
+
+            // Keep track of per-link sequence numbers if messages can
+            // be tagged.
+            let graph_validator = <$payload as Payload>::TAGGED
+                .then(|| crate::validation::GraphValidator::new(nodes_per_graph));
+            graph_validators.extend(graph_validator.clone());
+
+            // Create one task per graph node.
+            for (i, mut r) in receivers.into_iter().enumerate() {
+                // Clone the senders of all other nodes.
+                let mut other_senders: Vec<_> = senders
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(j, s)| if i != j { Some(s.clone()) } else { None })
+                    .collect();
+
+                // Clone the local sender.
+                let mut s = senders[i].clone();
+
+                let node_id = graph_id + pinball.graph_count * i;
+                let mut rng =
+                    oorandom::Rand64::new(((pinball.seed as u128) << 64) | node_id as u128);
+                let halted_visitors = halted_visitors.clone();
+                let graph_validator = graph_validator.clone();
+                // Index of the node targeted by each of the other
+                // senders.
+                let target_node = move |j: usize| if j < i { j } else { j + 1 };
+                let router = pinball.graph.router(i);
+                let mut checker = graph_validator.as_ref().map(|_| {
+                    crate::validation::SequenceChecker::new(
+                        graph_id * nodes_per_graph + i,
+                        nodes_per_graph,
+                        violations.clone(),
+                    )
+                });
+                // Sequence numbers of the messages sent to each node.
+                let mut seqs = vec![0; nodes_per_graph];
+                let gate = gate.clone();
+                let probe = monitor
+                    .map(|monitor| monitor.probe(format!("pinball graph {graph_id} node {i}")))
+                    .unwrap_or_default();
+
+                let spawn_start_time = Instant::now();
+                executor.spawn(async move {
+                    gate.wait().await;
+
+                    // The visitors are initially
+                    // distributed as uniformly as
+                    // possible between the nodes.
+                    let visitors = if i < visitor_count % nodes_per_graph {
+                        visitor_count / nodes_per_graph + 1
+                    } else {
+                        visitor_count / nodes_per_graph
+                    };
+                    for _ in 0..visitors {
+                        let mut visitor = <$payload as Payload>::new(0);
+                        if <$payload as Payload>::TAGGED {
+                            visitor.set_tag(i, seqs[i]);
+                            seqs[i] += 1;
+                        }
+                        probe.set(TaskState::Sending);
+                        let _ = s.send(visitor).await;
+                        probe.advance();
+                    }
+
+                    // All nodes increment the path length
+                    // of the received visitor and propagate
+                    // it to another node randomly.
+                    loop {
+                        probe.set(TaskState::Receiving);
+                        let mut visitor = match r.recv().await {
+                            // Stop if all senders were dropped.
+                            None => break,
+                            Some(v) => v,
+                        };
+                        probe.advance();
+                        if let (Some(checker), Some((sender_id, seq))) =
+                            (&mut checker, visitor.tag())
+                        {
+                            checker.check(sender_id, seq);
+                        }
+                        // Stop if the wind-down signal is received
+                        // or retrieve the path length of the
+                        // visitor.
+                        let path_length = match visitor.value() {
+                            usize::MAX => break,
+                            v => v + 1,
+                        };
+                        crate::utils::spin_for(work);
+
+                        if path_length < total_visitor_path_length {
+                            // Send the visitor to
+                            // another random node.
+                            let j = router.next(&mut rng);
+                            visitor.set_value(path_length);
+                            if <$payload as Payload>::TAGGED {
+                                visitor.set_tag(i, seqs[j]);
+                                seqs[j] += 1;
+                            }
+                            let target = if j < i { j } else { j - 1 };
+                            probe.set(TaskState::Sending);
+                            other_senders[target].send(visitor).await;
+                            probe.advance();
+                        } else {
+                            // The visitor has completed its
+                            // journey.
+                            let v = halted_visitors.fetch_add(1, Ordering::Relaxed);
+                            // Broadcast the wind-down
+                            // signal and exit if all
+                            // visitors are halted.
+                            if v + 1 == visitor_count {
+                                for (target, mut s) in other_senders.into_iter().enumerate() {
+                                    let mut signal = <$payload as Payload>::new(usize::MAX);
+                                    if <$payload as Payload>::TAGGED {
+                                        let j = target_node(target);
+                                        signal.set_tag(i, seqs[j]);
+                                        seqs[j] += 1;
+                                    }
+                                    probe.set(TaskState::Sending);
+                                    s.send(signal).await;
+                                    probe.advance();
+                                }
+                                break;
+                            }
+                        }
+                    }
+
+                    if let (Some(graph_validator), Some(checker)) = (graph_validator, checker) {
+                        graph_validator.complete(i, checker, seqs);
+                    }
+                });
+                spawn_time += Instant::now() - spawn_start_time;
+            }
+        }
+
+        (spawn_time, graph_validators)
+    }};
+}
+
+pub(crate) use spawn_pinball;
+
+/// Runs all samples for a given configuration, graph, number of visitors,
+/// channel constructor expression and message type and returns the measured
+/// throughputs, spawn times and resource usage.
+macro_rules! pinball {
+    ($executor:ty, $config:expr, $graph:expr, $visitor_count:expr, $channel:expr, $payload:ty) => {{
+        const TOTAL_PATH_LENGTH: usize = 1_000_000;
+        const GRAPH_COUNT: usize = 61;
+        let visitor_count: usize = $visitor_count;
+        let total_messages = (TOTAL_PATH_LENGTH / visitor_count) * visitor_count * GRAPH_COUNT;
+
+        let config: &crate::BenchConfig = $config;
+        let seed = config.seed;
+        let work = config.work;
+        let mut pinball = crate::benches::pinball::Pinball {
+            graph_count: GRAPH_COUNT,
+            graph: $graph,
+            visitor_count,
+            path_length: TOTAL_PATH_LENGTH / visitor_count,
+            seed: 0,
+        };
+
+        // Calibrate the spin loop outside of the timed region.
+        crate::utils::spin_for(work);
+
+        let mut throughput = Vec::new();
+        let mut spawn_times = Vec::new();
+        let mut measurements = Vec::new();
+
+        for sample in 0..config.samples.get() {
+            let mut executor = <$executor>::default();
+            pinball.seed = seed.sample_seed(sample);
+            let violations = crate::validation::Violations::default();
+            let gate = crate::utils::StartGate::default();
+
+            let (spawn_time, graph_validators) = crate::benches::pinball::spawn_pinball!(
+                &mut executor,
+                &pinball,
+                $channel,
+                $payload,
+                work,
+                &gate,
+                &violations,
+                None
+            );
+
+            let meter = crate::rusage::Meter::start();
+            let start_time = Instant::now();
+            gate.open();
+            executor.join_all();
+            let duration = Instant::now() - start_time;
+            let measurement = meter.stop();
+
+            for graph_validator in graph_validators {
+                graph_validator.finish(&violations);
+            }
+            violations.assert_none();
+
+            throughput.push(total_messages as f64 / duration.as_secs_f64());
+            spawn_times.push(spawn_time.as_secs_f64() * 1e6);
+            measurements.push(measurement);
+        }
+
+        (throughput, spawn_times, measurements)
+    }};
+}
+
 macro_rules! bench {
     ($channel_name:ident) => {
         pub mod $channel_name {
-            use std::num::NonZeroU32;
             use std::sync::atomic::{AtomicUsize, Ordering};
             use std::sync::Arc;
-            use std::time::Instant;
+            use std::time::{Duration, Instant};
 
             use oorandom;
 
+            use crate::benches::pinball::graph_from_config;
             use crate::channel_shims::$channel_name::channel;
             use crate::executor_shims::Executor;
-            use crate::{BenchIterator, BenchResult};
+            use crate::payload::{Payload, Tagged};
+            use crate::{BenchConfig, BenchIterator, BenchResult};
 
-            pub fn bench<E: Executor>(samples: NonZeroU32) -> BenchIterator {
-                const TOTAL_PATH_LENGTH: usize = 1_000_000;
-                const GRAPH_COUNT: usize = 61;
-                const NODES_PER_GRAPHS: usize = 13;
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
                 let results =
                     [1, 3, 7, 17, 41, 101, 241]
                         .into_iter()
                         .map(move |visitor_count: usize| {
-                            let total_messages =
-                                (TOTAL_PATH_LENGTH / visitor_count) * visitor_count * GRAPH_COUNT;
-
-                            let throughput: Vec<_> = (0..samples.get())
-                                .map(|_| {
-                                    let mut executor = E::default();
-                                    let total_visitor_path_length =
-                                        TOTAL_PATH_LENGTH / visitor_count;
-
-                                    for graph_id in 0..GRAPH_COUNT {
-                                        let mut senders = Vec::new();
-                                        let mut receivers = Vec::new();
-
-                                        // Build a sender-receiver pair for each graph
-                                        // node.
-                                        for _ in 0..NODES_PER_GRAPHS {
-                                            let (s, r) = channel(visitor_count);
-                                            senders.push(s);
-                                            receivers.push(r);
-                                        }
-
-                                        // Count how many visitors have completed their
-                                        // journey through the graph.
-                                        let halted_visitors = Arc::new(AtomicUsize::new(0));
-
-                                        // Create one task per graph node.
-                                        for (i, mut r) in receivers.into_iter().enumerate() {
-                                            // Clone the senders of all other nodes.
-                                            let mut other_senders: Vec<_> = senders
-                                                .iter()
-                                                .enumerate()
-                                                .filter_map(|(j, s)| {
-                                                    if i != j {
-                                                        Some(s.clone())
-                                                    } else {
-                                                        None
-                                                    }
-                                                })
-                                                .collect();
-
-                                            // Clone the local sender.
-                                            let mut s = senders[i].clone();
-
-                                            let seed = graph_id + GRAPH_COUNT * i;
-                                            let mut rng = oorandom::Rand64::new(seed as u128);
-                                            let halted_visitors = halted_visitors.clone();
-
-                                            executor.spawn(async move {
-                                                // The visitors are initially
-                                                // distributed as uniformly as
-                                                // possible between the nodes.
-                                                let visitors =
-                                                    if i < visitor_count % NODES_PER_GRAPHS {
-                                                        visitor_count / NODES_PER_GRAPHS + 1
-                                                    } else {
-                                                        visitor_count / NODES_PER_GRAPHS
-                                                    };
-                                                for _ in 0..visitors {
-                                                    let _ = s.send(0usize).await;
-                                                }
-
-                                                // All nodes increment the path length
-                                                // of the received visitor and propagate
-                                                // it to another node randomly.
-                                                loop {
-                                                    let mut path_length = match r.recv().await {
-                                                        // Stop if the wind-down signal
-                                                        // is received or if all senders
-                                                        // were dropped.
-                                                        Some(usize::MAX) | None => break,
-                                                        // Retrieve the path length of
-                                                        // the visitor.
-                                                        Some(v) => v,
-                                                    };
-
-                                                    path_length += 1;
-
-                                                    if path_length < total_visitor_path_length {
-                                                        // Send the visitor to
-                                                        // another random node.
-                                                        let target = rng.rand_range(
-                                                            0..other_senders.len() as u64,
-                                                        );
-                                                        other_senders[target as usize]
-                                                            .send(path_length)
-                                                            .await;
-                                                    } else {
-                                                        // The visitor has completed its
-                                                        // journey.
-                                                        let v = halted_visitors
-                                                            .fetch_add(1, Ordering::Relaxed);
-                                                        // Broadcast the wind-down
-                                                        // signal and exit if all
-                                                        // visitors are halted.
-                                                        if v + 1 == visitor_count {
-                                                            for mut s in other_senders {
-                                                                s.send(usize::MAX).await
-                                                            }
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                            });
-                                        }
-                                    }
-
-                                    let start_time = Instant::now();
-                                    executor.join_all();
-                                    let duration = Instant::now() - start_time;
-
-                                    total_messages as f64 / duration.as_secs_f64()
-                                })
-                                .collect();
+                            let (throughput, spawn_time, usage) = if config.validate {
+                                pinball!(
+                                    E,
+                                    &config,
+                                    graph_from_config(&config),
+                                    visitor_count,
+                                    channel(visitor_count),
+                                    Tagged
+                                )
+                            } else {
+                                pinball!(
+                                    E,
+                                    &config,
+                                    graph_from_config(&config),
+                                    visitor_count,
+                                    channel(visitor_count),
+                                    usize
+                                )
+                            };
 
                             BenchResult::new(
                                 String::from("ball count"),
                                 visitor_count.to_string(),
                                 throughput,
                             )
+                            .with_metric("spawn time", "µs", spawn_time)
+                            .with_resource_usage(usage)
                         });
 
                 Box::new(results)
@@ -148,3 +333,221 @@ macro_rules! bench {
 }
 
 crate::macros::add_bench!();
+
+/// Pinball benchmark with unbounded channels.
+pub mod unbounded {
+    macro_rules! bench {
+        ($channel_name:ident) => {
+            pub mod $channel_name {
+                use std::sync::atomic::{AtomicUsize, Ordering};
+                use std::sync::Arc;
+                use std::time::{Duration, Instant};
+
+                use oorandom;
+
+                use crate::benches::pinball::graph_from_config;
+                use crate::channel_shims::$channel_name::unbounded_channel;
+                use crate::executor_shims::Executor;
+                use crate::payload::{Payload, Tagged};
+                use crate::{BenchConfig, BenchIterator, BenchResult};
+
+                pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                    let results =
+                        [1, 3, 7, 17, 41, 101, 241]
+                            .into_iter()
+                            .map(move |visitor_count: usize| {
+                                let (throughput, spawn_time, usage) = if config.validate {
+                                    pinball!(
+                                        E,
+                                        &config,
+                                        graph_from_config(&config),
+                                        visitor_count,
+                                        unbounded_channel(),
+                                        Tagged
+                                    )
+                                } else {
+                                    pinball!(
+                                        E,
+                                        &config,
+                                        graph_from_config(&config),
+                                        visitor_count,
+                                        unbounded_channel(),
+                                        usize
+                                    )
+                                };
+
+                                BenchResult::new(
+                                    String::from("ball count"),
+                                    visitor_count.to_string(),
+                                    throughput,
+                                )
+                                .with_metric("spawn time", "µs", spawn_time)
+                                .with_resource_usage(usage)
+                            });
+
+                    Box::new(results)
+                }
+            }
+        };
+    }
+
+    crate::macros::add_unbounded_bench!();
+}
+
+/// Pinball benchmark with various message types.
+pub mod payload {
+    macro_rules! bench {
+        ($channel_name:ident) => {
+            pub mod $channel_name {
+                use std::sync::atomic::{AtomicUsize, Ordering};
+                use std::sync::Arc;
+                use std::time::{Duration, Instant};
+
+                use oorandom;
+
+                use crate::benches::pinball::graph_from_config;
+                use crate::channel_shims::$channel_name::channel;
+                use crate::executor_shims::Executor;
+                use crate::payload::{Boxed, Inline, Payload};
+                use crate::rusage::Measurement;
+                use crate::{BenchConfig, BenchIterator, BenchResult};
+
+                pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                    const VISITOR_COUNT: usize = 41;
+
+                    let payloads: [(
+                        &str,
+                        fn(&BenchConfig) -> (Vec<f64>, Vec<f64>, Vec<Option<Measurement>>),
+                    ); 5] = [
+                        ("8", |config| {
+                            pinball!(
+                                E,
+                                config,
+                                graph_from_config(config),
+                                VISITOR_COUNT,
+                                channel(VISITOR_COUNT),
+                                Inline<8>
+                            )
+                        }),
+                        ("64", |config| {
+                            pinball!(
+                                E,
+                                config,
+                                graph_from_config(config),
+                                VISITOR_COUNT,
+                                channel(VISITOR_COUNT),
+                                Inline<64>
+                            )
+                        }),
+                        ("256", |config| {
+                            pinball!(
+                                E,
+                                config,
+                                graph_from_config(config),
+                                VISITOR_COUNT,
+                                channel(VISITOR_COUNT),
+                                Inline<256>
+                            )
+                        }),
+                        ("1024", |config| {
+                            pinball!(
+                                E,
+                                config,
+                                graph_from_config(config),
+                                VISITOR_COUNT,
+                                channel(VISITOR_COUNT),
+                                Inline<1024>
+                            )
+                        }),
+                        ("boxed_1024", |config| {
+                            pinball!(
+                                E,
+                                config,
+                                graph_from_config(config),
+                                VISITOR_COUNT,
+                                channel(VISITOR_COUNT),
+                                Boxed<1024>
+                            )
+                        }),
+                    ];
+
+                    let results = payloads.into_iter().map(move |(size, run)| {
+                        let (throughput, spawn_time, usage) = run(&config);
+
+                        BenchResult::new(String::from("payload"), size.to_string(), throughput)
+                            .with_metric("spawn time", "µs", spawn_time)
+                            .with_resource_usage(usage)
+                    });
+
+                    Box::new(results)
+                }
+            }
+        };
+    }
+
+    crate::macros::add_bench!();
+}
+
+/// Pinball benchmark with various routing distributions.
+pub mod routing {
+    macro_rules! bench {
+        ($channel_name:ident) => {
+            pub mod $channel_name {
+                use std::sync::atomic::{AtomicUsize, Ordering};
+                use std::sync::Arc;
+                use std::time::{Duration, Instant};
+
+                use oorandom;
+
+                use crate::benches::pinball::NODES_PER_GRAPH;
+                use crate::channel_shims::$channel_name::channel;
+                use crate::executor_shims::Executor;
+                use crate::graph::Graph;
+                use crate::payload::{Payload, Tagged};
+                use crate::{BenchConfig, BenchIterator, BenchResult};
+
+                pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                    const VISITOR_COUNT: usize = 41;
+
+                    let graphs: [(&str, fn(usize) -> Graph); 4] = [
+                        ("uniform", Graph::complete),
+                        ("zipf", Graph::zipf),
+                        ("ring", Graph::ring),
+                        ("star", Graph::star),
+                    ];
+
+                    let results = graphs.into_iter().map(move |(routing, graph)| {
+                        let graph = graph(NODES_PER_GRAPH);
+                        let (throughput, spawn_time, usage) = if config.validate {
+                            pinball!(
+                                E,
+                                &config,
+                                graph,
+                                VISITOR_COUNT,
+                                channel(VISITOR_COUNT),
+                                Tagged
+                            )
+                        } else {
+                            pinball!(
+                                E,
+                                &config,
+                                graph,
+                                VISITOR_COUNT,
+                                channel(VISITOR_COUNT),
+                                usize
+                            )
+                        };
+
+                        BenchResult::new(String::from("routing"), routing.to_string(), throughput)
+                            .with_metric("spawn time", "µs", spawn_time)
+                            .with_resource_usage(usage)
+                    });
+
+                    Box::new(results)
+                }
+            }
+        };
+    }
+
+    crate::macros::add_bench!();
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+
+/// A request carrying its own reply channel.
+///
+/// The reply sender is optional only because some channels require messages
+/// to implement `Default`.
+pub struct Request<R> {
+    pub value: usize,
+    pub reply: Option<R>,
+}
+
+impl<R> Default for Request<R> {
+    fn default() -> Self {
+        Self {
+            value: 0,
+            reply: None,
+        }
+    }
+}
+
+impl<R> fmt::Debug for Request<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The MPSC channel shims used to send requests.
+pub mod shims {
+    pub use crate::channel_shims::{
+        async_channel, flume, futures_mpsc, postage_mpsc, tachyonix, tokio_mpsc,
+    };
+
+    /// A `thingbuf` shim for messages that cannot be cloned.
+    ///
+    /// The default recycling policy of `thingbuf` clones the default value
+    /// into the slots, which requires messages to be `Clone` while reply
+    /// channels cannot be cloned. This policy resets the slots instead.
+    pub mod thingbuf {
+        use ::thingbuf::mpsc as channel;
+        use ::thingbuf::Recycle;
+
+        pub struct ResetRecycle;
+        impl<T: Default> Recycle<T> for ResetRecycle {
+            fn new_element(&self) -> T {
+                T::default()
+            }
+            fn recycle(&self, element: &mut T) {
+                *element = T::default();
+            }
+        }
+
+        pub struct Sender<T> {
+            inner: channel::Sender<T, ResetRecycle>,
+        }
+        impl<T> Clone for Sender<T> {
+            fn clone(&self) -> Self {
+                Self {
+                    inner: self.inner.clone(),
+                }
+            }
+        }
+        impl<T: std::fmt::Debug + Default> Sender<T> {
+            pub async fn send(&mut self, message: T) {
+                self.inner.send(message).await.unwrap();
+            }
+        }
+
+        pub struct Receiver<T> {
+            inner: channel::Receiver<T, ResetRecycle>,
+        }
+        impl<T: Default> Receiver<T> {
+            pub async fn recv(&mut self) -> Option<T> {
+                self.inner.recv().await
+            }
+        }
+
+        pub fn channel<T: Default>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+            let (s, r) = channel::with_recycle(capacity, ResetRecycle);
+            (Sender { inner: s }, Receiver { inner: r })
+        }
+    }
+}
+
+/// Runs all samples for a given configuration and reply channel shim and
+/// returns the measured throughputs and mean latencies.
+macro_rules! rpc {
+    ($executor:ty, $config:expr, $reply:ident) => {{
+        use crate::oneshot_shims::$reply as reply_channel;
+
+        const REQUESTS_PER_SERVER: usize = 200_000;
+        const SERVERS: usize = 61;
+        const CLIENTS_PER_SERVER: usize = 13;
+        let requests_per_client = REQUESTS_PER_SERVER / CLIENTS_PER_SERVER;
+        let total_requests = requests_per_client * CLIENTS_PER_SERVER * SERVERS;
+        let config: &crate::BenchConfig = $config;
+
+        (0..config.samples.get())
+            .map(|_| {
+                let mut executor = <$executor>::default();
+                let total_latency_ns = Arc::new(AtomicU64::new(0));
+
+                for _ in 0..SERVERS {
+                    // Each client has at most one pending request, so
+                    // requests never block.
+                    let (s, mut r) = channel(CLIENTS_PER_SERVER);
+
+                    for _ in 0..CLIENTS_PER_SERVER {
+                        let mut s = s.clone();
+                        let total_latency_ns = total_latency_ns.clone();
+
+                        executor.spawn(async move {
+                            let mut latency_ns = 0;
+                            for i in 0..requests_per_client {
+                                let (reply_s, reply_r) = reply_channel::channel();
+
+                                let request_time = Instant::now();
+                                s.send(Request {
+                                    value: i,
+                                    reply: Some(reply_s),
+                                })
+                                .await;
+                                let response = reply_r.recv().await.unwrap();
+                                latency_ns += (Instant::now() - request_time).as_nanos() as u64;
+
+                                assert_eq!(response, i + 1);
+                            }
+                            total_latency_ns.fetch_add(latency_ns, Ordering::Relaxed);
+                        });
+                    }
+
+                    // The server stops once all clients are done and have
+                    // dropped their sender.
+                    drop(s);
+                    executor.spawn(async move {
+                        while let Some(Request { value, reply }) = r.recv().await {
+                            reply.unwrap().send(value + 1);
+                        }
+                    })
+                }
+
+                let start_time = Instant::now();
+                executor.join_all();
+                let duration = Instant::now() - start_time;
+
+                let mean_latency =
+                    total_latency_ns.load(Ordering::Relaxed) as f64 * 1e-3 / total_requests as f64;
+
+                (total_requests as f64 / duration.as_secs_f64(), mean_latency)
+            })
+            .unzip::<_, _, Vec<_>, Vec<_>>()
+    }};
+}
+
+macro_rules! bench {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::sync::atomic::{AtomicU64, Ordering};
+            use std::sync::Arc;
+            use std::time::Instant;
+
+            use crate::benches::rpc::shims::$channel_name::channel;
+            use crate::benches::rpc::Request;
+            use crate::executor_shims::Executor;
+            use crate::{BenchConfig, BenchIterator, BenchResult};
+
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                let replies: [(&str, fn(&BenchConfig) -> (Vec<f64>, Vec<f64>)); 4] = [
+                    ("async_channel", |config| rpc!(E, config, async_channel)),
+                    ("futures", |config| rpc!(E, config, futures_oneshot)),
+                    ("oneshot", |config| rpc!(E, config, oneshot)),
+                    ("tokio", |config| rpc!(E, config, tokio_oneshot)),
+                ];
+
+                let results = replies.into_iter().map(move |(reply, run)| {
+                    let (throughput, latency) = run(&config);
+
+                    BenchResult::new(String::from("reply"), reply.to_string(), throughput)
+                        .with_metric("latency", "µs", latency)
+                });
+
+                Box::new(results)
+            }
+        }
+    };
+}
+
+crate::macros::add_bench!();
@@ -0,0 +1,79 @@
+macro_rules! bench {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::time::Instant;
+
+            use crate::channel_shims::$channel_name::channel;
+            use crate::executor_shims::Executor;
+            use crate::payload::{Counted, DropTracker};
+            use crate::{BenchConfig, BenchIterator, BenchResult};
+
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                let samples = config.samples;
+                const MESSAGES: usize = 1_000_000;
+                const TASKS: usize = 61;
+
+                let results = [10, 100, 1000, 10000]
+                    .into_iter()
+                    .map(move |capacity: usize| {
+                        let channel_count = MESSAGES / capacity;
+
+                        let throughput: Vec<_> = (0..samples.get())
+                            .map(|_| {
+                                let mut executor = E::default();
+                                let tracker = DropTracker::new(channel_count * capacity);
+
+                                // Fill all channels outside of the timed region.
+                                let mut message_count = 0;
+                                let mut in_flight_count = 0;
+                                let mut channels = Vec::with_capacity(channel_count);
+                                for _ in 0..channel_count {
+                                    let (mut s, r) = channel(capacity);
+                                    for _ in 0..capacity {
+                                        let message = Counted::new(message_count, &tracker);
+                                        message_count += 1;
+                                        if s.try_send(message).is_err() {
+                                            break;
+                                        }
+                                        in_flight_count += 1;
+                                    }
+                                    channels.push((s, r));
+                                }
+
+                                // Each task drains half of the in-flight messages
+                                // of its channels and then drops the channels
+                                // with the remaining messages.
+                                let channels_per_task = channel_count.div_ceil(TASKS);
+                                while !channels.is_empty() {
+                                    let split = channels.len().saturating_sub(channels_per_task);
+                                    let task_channels = channels.split_off(split);
+
+                                    executor.spawn(async move {
+                                        for (_s, mut r) in task_channels {
+                                            for _ in 0..capacity / 2 {
+                                                r.try_recv();
+                                            }
+                                        }
+                                    });
+                                }
+
+                                let start_time = Instant::now();
+                                executor.join_all();
+                                let duration = Instant::now() - start_time;
+
+                                tracker.assert_all_dropped(message_count);
+
+                                in_flight_count as f64 / duration.as_secs_f64()
+                            })
+                            .collect();
+
+                        BenchResult::new(String::from("capacity"), capacity.to_string(), throughput)
+                    });
+
+                Box::new(results)
+            }
+        }
+    };
+}
+
+crate::macros::add_bench!();
@@ -0,0 +1,90 @@
+macro_rules! bench {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            use std::sync::Arc;
+            use std::time::Instant;
+
+            use crate::executor_shims::Executor;
+            use crate::utils::yield_now;
+            use crate::watch_shims::$channel_name::channel;
+            use crate::{BenchConfig, BenchIterator, BenchResult};
+
+            pub fn bench<E: Executor>(config: BenchConfig) -> BenchIterator {
+                let samples = config.samples;
+                const UPDATES_PER_CHANNEL: usize = 100_000;
+                const CHANNELS: usize = 61;
+
+                let results = [1, 4, 16, 64].into_iter().map(move |reader_count: usize| {
+                    let (throughput, coalescing): (Vec<_>, Vec<_>) = (0..samples.get())
+                        .map(|_| {
+                            let mut executor = E::default();
+                            let observed_updates = Arc::new(AtomicUsize::new(0));
+
+                            for _ in 0..CHANNELS {
+                                let (mut s, r) = channel(0usize);
+
+                                for _ in 0..reader_count {
+                                    let mut r = r.clone();
+                                    let observed_updates = observed_updates.clone();
+
+                                    executor.spawn(async move {
+                                        // Count the updates actually observed
+                                        // until the last one, ignoring the
+                                        // initial value.
+                                        let mut observed = 0;
+                                        loop {
+                                            match r.changed().await {
+                                                Some(0) => {}
+                                                Some(UPDATES_PER_CHANNEL) => {
+                                                    observed += 1;
+                                                    break;
+                                                }
+                                                Some(_) => observed += 1,
+                                                None => panic!("the writer was dropped"),
+                                            }
+                                        }
+                                        observed_updates.fetch_add(observed, Ordering::Relaxed);
+                                    });
+                                }
+
+                                // Only the readers' receivers should be kept
+                                // alive.
+                                drop(r);
+                                executor.spawn(async move {
+                                    for i in 1..=UPDATES_PER_CHANNEL {
+                                        s.send(i).await;
+                                        yield_now().await;
+                                    }
+                                });
+                            }
+
+                            let start_time = Instant::now();
+                            executor.join_all();
+                            let duration = Instant::now() - start_time;
+
+                            let observed_updates = observed_updates.load(Ordering::Relaxed);
+                            let published_updates = UPDATES_PER_CHANNEL * CHANNELS * reader_count;
+
+                            (
+                                observed_updates as f64 / duration.as_secs_f64(),
+                                published_updates as f64 / observed_updates as f64,
+                            )
+                        })
+                        .unzip();
+
+                    BenchResult::new(
+                        String::from("reader count"),
+                        reader_count.to_string(),
+                        throughput,
+                    )
+                    .with_metric("coalescing", "upd/obs", coalescing)
+                });
+
+                Box::new(results)
+            }
+        }
+    };
+}
+
+crate::macros::add_watch_bench!();
@@ -1,14 +1,24 @@
 pub mod async_channel {
     use ::async_channel as channel;
 
-    #[derive(Clone)]
     pub struct Sender<T> {
         inner: channel::Sender<T>,
     }
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
     impl<T> Sender<T> {
         pub async fn send(&mut self, message: T) {
             self.inner.send(message).await.unwrap();
         }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.try_send(message).map_err(|e| e.into_inner())
+        }
     }
 
     pub struct Receiver<T> {
@@ -18,25 +28,44 @@ pub mod async_channel {
         pub async fn recv(&mut self) -> Option<T> {
             self.inner.recv().await.ok()
         }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
     }
 
     pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
         let (s, r) = channel::bounded(capacity);
         (Sender { inner: s }, Receiver { inner: r })
     }
+
+    pub fn unbounded_channel<T>() -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::unbounded();
+        (Sender { inner: s }, Receiver { inner: r })
+    }
 }
 
 pub mod flume {
     use ::flume as channel;
 
-    #[derive(Clone)]
     pub struct Sender<T> {
         inner: channel::Sender<T>,
     }
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
     impl<T> Sender<T> {
         pub async fn send(&mut self, message: T) {
             self.inner.send_async(message).await.unwrap();
         }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.try_send(message).map_err(|e| e.into_inner())
+        }
     }
 
     pub struct Receiver<T> {
@@ -46,12 +75,21 @@ pub mod flume {
         pub async fn recv(&mut self) -> Option<T> {
             self.inner.recv_async().await.ok()
         }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
     }
 
     pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
         let (s, r) = channel::bounded(capacity);
         (Sender { inner: s }, Receiver { inner: r })
     }
+
+    pub fn unbounded_channel<T>() -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::unbounded();
+        (Sender { inner: s }, Receiver { inner: r })
+    }
 }
 
 pub mod futures_mpsc {
@@ -61,14 +99,24 @@ pub mod futures_mpsc {
 
     use std::fmt::Debug;
 
-    #[derive(Clone)]
     pub struct Sender<T> {
         inner: channel::Sender<T>,
     }
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
     impl<T: Debug> Sender<T> {
         pub async fn send(&mut self, message: T) {
             self.inner.send(message).await.unwrap();
         }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.try_send(message).map_err(|e| e.into_inner())
+        }
     }
 
     pub struct Receiver<T> {
@@ -78,29 +126,85 @@ pub mod futures_mpsc {
         pub async fn recv(&mut self) -> Option<T> {
             self.inner.next().await
         }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
     }
 
     pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
         let (s, r) = channel::channel(capacity);
         (Sender { inner: s }, Receiver { inner: r })
     }
+
+    pub struct UnboundedSender<T> {
+        inner: channel::UnboundedSender<T>,
+    }
+    impl<T> Clone for UnboundedSender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
+    impl<T: Debug> UnboundedSender<T> {
+        pub async fn send(&mut self, message: T) {
+            self.inner.unbounded_send(message).unwrap();
+        }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner
+                .unbounded_send(message)
+                .map_err(|e| e.into_inner())
+        }
+    }
+
+    pub struct UnboundedReceiver<T> {
+        inner: channel::UnboundedReceiver<T>,
+    }
+    impl<T> UnboundedReceiver<T> {
+        pub async fn recv(&mut self) -> Option<T> {
+            self.inner.next().await
+        }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
+    }
+
+    pub fn unbounded_channel<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+        let (s, r) = channel::unbounded();
+        (UnboundedSender { inner: s }, UnboundedReceiver { inner: r })
+    }
 }
 
 pub mod postage_mpsc {
     use ::postage::mpsc as channel;
-    use ::postage::sink::Sink;
+    use ::postage::sink::{Sink, TrySendError};
     use ::postage::stream::Stream;
 
     use std::fmt::Debug;
 
-    #[derive(Clone)]
     pub struct Sender<T> {
         inner: channel::Sender<T>,
     }
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
     impl<T: Debug> Sender<T> {
         pub async fn send(&mut self, message: T) {
             self.inner.send(message).await.unwrap();
         }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.try_send(message).map_err(|e| match e {
+                TrySendError::Pending(message) | TrySendError::Rejected(message) => message,
+            })
+        }
     }
 
     pub struct Receiver<T> {
@@ -110,6 +214,10 @@ pub mod postage_mpsc {
         pub async fn recv(&mut self) -> Option<T> {
             self.inner.recv().await
         }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
     }
 
     pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
@@ -121,14 +229,28 @@ pub mod postage_mpsc {
 pub mod tachyonix {
     use ::tachyonix as channel;
 
-    #[derive(Clone)]
     pub struct Sender<T> {
         inner: channel::Sender<T>,
     }
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
     impl<T: std::fmt::Debug> Sender<T> {
         pub async fn send(&mut self, message: T) {
             self.inner.send(message).await.unwrap();
         }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.try_send(message).map_err(|e| match e {
+                channel::TrySendError::Full(message) | channel::TrySendError::Closed(message) => {
+                    message
+                }
+            })
+        }
     }
 
     pub struct Receiver<T> {
@@ -138,6 +260,10 @@ pub mod tachyonix {
         pub async fn recv(&mut self) -> Option<T> {
             self.inner.recv().await.ok()
         }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
     }
 
     pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
@@ -157,6 +283,10 @@ pub mod thingbuf {
         pub async fn send(&mut self, message: T) {
             self.inner.send(message).await.unwrap();
         }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.try_send(message).map_err(|e| e.into_inner())
+        }
     }
 
     pub struct Receiver<T> {
@@ -166,6 +296,10 @@ pub mod thingbuf {
         pub async fn recv(&mut self) -> Option<T> {
             self.inner.recv().await
         }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
     }
 
     pub fn channel<T: Default + Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
@@ -179,14 +313,27 @@ pub mod tokio_mpsc {
 
     use std::fmt::Debug;
 
-    #[derive(Clone)]
     pub struct Sender<T> {
         inner: channel::Sender<T>,
     }
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
     impl<T: Debug> Sender<T> {
         pub async fn send(&mut self, message: T) {
             self.inner.send(message).await.unwrap();
         }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.try_send(message).map_err(|e| match e {
+                channel::error::TrySendError::Full(message)
+                | channel::error::TrySendError::Closed(message) => message,
+            })
+        }
     }
 
     pub struct Receiver<T> {
@@ -196,10 +343,52 @@ pub mod tokio_mpsc {
         pub async fn recv(&mut self) -> Option<T> {
             self.inner.recv().await
         }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
     }
 
     pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
         let (s, r) = channel::channel(capacity);
         (Sender { inner: s }, Receiver { inner: r })
     }
+
+    pub struct UnboundedSender<T> {
+        inner: channel::UnboundedSender<T>,
+    }
+    impl<T> Clone for UnboundedSender<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
+    impl<T: Debug> UnboundedSender<T> {
+        pub async fn send(&mut self, message: T) {
+            self.inner.send(message).unwrap();
+        }
+
+        pub fn try_send(&mut self, message: T) -> Result<(), T> {
+            self.inner.send(message).map_err(|e| e.0)
+        }
+    }
+
+    pub struct UnboundedReceiver<T> {
+        inner: channel::UnboundedReceiver<T>,
+    }
+    impl<T> UnboundedReceiver<T> {
+        pub async fn recv(&mut self) -> Option<T> {
+            self.inner.recv().await
+        }
+
+        pub fn try_recv(&mut self) -> Option<T> {
+            self.inner.try_recv().ok()
+        }
+    }
+
+    pub fn unbounded_channel<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+        let (s, r) = channel::unbounded_channel();
+        (UnboundedSender { inner: s }, UnboundedReceiver { inner: r })
+    }
 }
@@ -0,0 +1,237 @@
+//! Conformance checks of the close and disconnection semantics of channels.
+//!
+//! Futures are polled manually with a waker that records wake-ups, so a
+//! non-conforming channel results in a failed check rather than a hang.
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+/// The checks performed on each channel, with their description.
+pub const CHECKS: &[(&str, &str)] = &[
+    (
+        "close",
+        "receiver gets buffered messages, then `None` once the last sender is dropped",
+    ),
+    (
+        "send_error",
+        "`try_send` fails once the receiver is dropped",
+    ),
+    (
+        "async_send",
+        "an async send fails rather than hangs once the receiver is dropped",
+    ),
+    ("wake_send", "a pending receiver is woken by a send"),
+    (
+        "wake_close",
+        "a pending receiver is woken when the last sender is dropped",
+    ),
+];
+
+macro_rules! check {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::task::Poll;
+
+            use crate::channel_shims::$channel_name::channel;
+
+            use super::{poll_once, poll_once_catching};
+
+            /// Runs all checks and returns an error message for each failed
+            /// check.
+            pub fn check() -> Vec<Result<(), String>> {
+                vec![
+                    close(),
+                    send_error(),
+                    async_send(),
+                    wake_send(),
+                    wake_close(),
+                ]
+            }
+
+            fn close() -> Result<(), String> {
+                let (mut s1, mut r) = channel(10);
+                let mut s2 = s1.clone();
+                s1.try_send(1usize)
+                    .map_err(|_| "could not send a message")?;
+                s2.try_send(2).map_err(|_| "could not send a message")?;
+                drop(s1);
+                drop(s2);
+
+                for expected in [Some(1), Some(2), None] {
+                    match poll_once(r.recv()).0 {
+                        Poll::Ready(v) if v == expected => {}
+                        Poll::Ready(v) => {
+                            return Err(format!("received {v:?} instead of {expected:?}"))
+                        }
+                        Poll::Pending => {
+                            return Err(format!("pending instead of receiving {expected:?}"))
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+
+            fn send_error() -> Result<(), String> {
+                let (mut s, r) = channel(10);
+                drop(r);
+
+                match s.try_send(1usize) {
+                    Ok(()) => Err(String::from("message sent to a dropped receiver")),
+                    Err(_) => Ok(()),
+                }
+            }
+
+            fn async_send() -> Result<(), String> {
+                let (mut s, r) = channel(10);
+                drop(r);
+
+                // The shims panic when an async send fails.
+                match poll_once_catching(s.send(1usize)) {
+                    Some(Poll::Ready(())) => {
+                        Err(String::from("message sent to a dropped receiver"))
+                    }
+                    Some(Poll::Pending) => Err(String::from("pending send to a dropped receiver")),
+                    None => Ok(()),
+                }
+            }
+
+            fn wake_send() -> Result<(), String> {
+                let (mut s, mut r) = channel(10);
+
+                let mut recv = Box::pin(r.recv());
+                let (poll, waker) = poll_once(recv.as_mut());
+                if poll.is_ready() {
+                    return Err(String::from("empty channel is ready"));
+                }
+                s.try_send(1usize).map_err(|_| "could not send a message")?;
+                if !waker.is_woken() {
+                    return Err(String::from("receiver was not woken"));
+                }
+                match poll_once(recv.as_mut()).0 {
+                    Poll::Ready(Some(1)) => Ok(()),
+                    poll => Err(format!("polled {poll:?} after wake-up")),
+                }
+            }
+
+            fn wake_close() -> Result<(), String> {
+                let (s, mut r) = channel::<usize>(10);
+
+                let mut recv = Box::pin(r.recv());
+                let (poll, waker) = poll_once(recv.as_mut());
+                if poll.is_ready() {
+                    return Err(String::from("empty channel is ready"));
+                }
+                drop(s);
+                if !waker.is_woken() {
+                    return Err(String::from("receiver was not woken"));
+                }
+                match poll_once(recv.as_mut()).0 {
+                    Poll::Ready(None) => Ok(()),
+                    poll => Err(format!("polled {poll:?} after wake-up")),
+                }
+            }
+        }
+    };
+}
+
+check!(async_channel);
+check!(flume);
+check!(futures_mpsc);
+check!(tachyonix);
+check!(thingbuf);
+check!(postage_mpsc);
+check!(tokio_mpsc);
+
+#[allow(clippy::type_complexity)]
+pub const CHANNELS: &[(&str, fn() -> Vec<Result<(), String>>)] = &[
+    ("async_channel", async_channel::check),
+    ("flume", flume::check),
+    ("futures_mpsc", futures_mpsc::check),
+    ("tachyonix", tachyonix::check),
+    ("thingbuf", thingbuf::check),
+    ("postage_mpsc", postage_mpsc::check),
+    ("tokio_mpsc", tokio_mpsc::check),
+];
+
+/// Polls a future once with a waker that records wake-ups.
+fn poll_once<F: Future>(future: F) -> (Poll<F::Output>, Arc<FlagWaker>) {
+    let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let poll = pin!(future).poll(&mut cx);
+
+    (poll, flag)
+}
+
+/// Polls a future once like [`poll_once`], returning `None` if polling
+/// panicked.
+///
+/// The panic message is not printed.
+fn poll_once_catching<F: Future>(future: F) -> Option<Poll<F::Output>> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let poll = panic::catch_unwind(AssertUnwindSafe(|| poll_once(future).0)).ok();
+    panic::set_hook(hook);
+
+    poll
+}
+
+struct FlagWaker(AtomicBool);
+
+impl FlagWaker {
+    fn is_woken(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Wake for FlagWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Runs the checks on all channels and prints a compatibility matrix.
+pub fn run() {
+    println!("Running conformance checks.");
+    print!("    {:<15}", "");
+    for (name, _) in CHECKS {
+        print!(" {name:>12}");
+    }
+    println!();
+
+    let mut failures = Vec::new();
+    for (channel_name, check) in CHANNELS {
+        print!("    {channel_name:<15}");
+        for ((check_name, _), result) in CHECKS.iter().zip(check()) {
+            match result {
+                Ok(()) => print!(" {:>12}", "pass"),
+                Err(e) => {
+                    print!(" {:>12}", "FAIL");
+                    failures.push(format!("{channel_name}/{check_name}: {e}"));
+                }
+            }
+        }
+        println!();
+    }
+    println!();
+
+    println!("Checks:");
+    for (name, description) in CHECKS {
+        println!("    {name:<12} {description}");
+    }
+    if !failures.is_empty() {
+        println!();
+        println!("Failures:");
+        for failure in failures {
+            println!("    {failure}");
+        }
+    }
+}
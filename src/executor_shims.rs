@@ -1,10 +1,136 @@
+use std::fmt;
 use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "smol")]
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::thread::JoinHandle;
+
+use crate::affinity;
 
+/// An executor shim.
+///
+/// The worker threads of an executor are started when it is created with
+/// `default` and stopped when it is dropped: dropping an executor waits until
+/// all its worker threads have exited, so each sample starts with the same set
+/// of worker threads and leaves no background thread behind.
 pub trait Executor: Default {
+    /// Whether the worker threads are owned by the executor and therefore
+    /// stopped when it is dropped.
+    const OWNS_THREADS: bool = true;
+
     fn spawn<T: Future<Output = ()> + Send + 'static>(&mut self, future: T);
     fn join_all(&mut self);
 }
 
+/// Tuning options applied to the builders of all Tokio runtimes.
+#[derive(Default)]
+pub struct TokioOptions {
+    event_interval: Option<u32>,
+    global_queue_interval: Option<u32>,
+    /// Whether the LIFO slot is enabled, if specified.
+    lifo_slot: Option<bool>,
+    max_blocking_threads: Option<usize>,
+}
+
+static TOKIO_OPTIONS: OnceLock<TokioOptions> = OnceLock::new();
+
+impl TokioOptions {
+    /// Parses a comma-separated list of `key=value` options such as
+    /// `lifo=off,global_queue_interval=31`.
+    pub fn parse(options: &str) -> Result<Self, String> {
+        let mut tokio_options = Self::default();
+        for option in options.split(',') {
+            let invalid = || format!("Invalid tokio option '{option}'");
+            let (key, value) = option.split_once('=').ok_or_else(invalid)?;
+            match key {
+                "event_interval" => {
+                    let value = value.parse().map_err(|_| invalid())?;
+                    if value == 0 {
+                        return Err(invalid());
+                    }
+                    tokio_options.event_interval = Some(value);
+                }
+                "global_queue_interval" => {
+                    let value = value.parse().map_err(|_| invalid())?;
+                    if value == 0 {
+                        return Err(invalid());
+                    }
+                    tokio_options.global_queue_interval = Some(value);
+                }
+                "lifo" => {
+                    let lifo_slot = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(invalid()),
+                    };
+                    if !lifo_slot && !cfg!(tokio_unstable) {
+                        return Err(String::from(
+                            "Disabling the LIFO slot requires building with \
+                             RUSTFLAGS=\"--cfg tokio_unstable\"",
+                        ));
+                    }
+                    tokio_options.lifo_slot = Some(lifo_slot);
+                }
+                "max_blocking_threads" => {
+                    let value = value.parse().map_err(|_| invalid())?;
+                    if value == 0 {
+                        return Err(invalid());
+                    }
+                    tokio_options.max_blocking_threads = Some(value);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(tokio_options)
+    }
+
+    /// Sets the options used by all Tokio runtimes created from now on.
+    pub fn set(self) -> &'static Self {
+        TOKIO_OPTIONS.get_or_init(|| self)
+    }
+
+    fn apply(builder: &mut ::tokio::runtime::Builder) {
+        let Some(options) = TOKIO_OPTIONS.get() else {
+            return;
+        };
+        if let Some(event_interval) = options.event_interval {
+            builder.event_interval(event_interval);
+        }
+        if let Some(global_queue_interval) = options.global_queue_interval {
+            builder.global_queue_interval(global_queue_interval);
+        }
+        #[cfg(tokio_unstable)]
+        if options.lifo_slot == Some(false) {
+            builder.disable_lifo_slot();
+        }
+        if let Some(max_blocking_threads) = options.max_blocking_threads {
+            builder.max_blocking_threads(max_blocking_threads);
+        }
+    }
+}
+
+impl fmt::Display for TokioOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut options = Vec::new();
+        if let Some(event_interval) = self.event_interval {
+            options.push(format!("event_interval={event_interval}"));
+        }
+        if let Some(global_queue_interval) = self.global_queue_interval {
+            options.push(format!("global_queue_interval={global_queue_interval}"));
+        }
+        if let Some(lifo_slot) = self.lifo_slot {
+            options.push(format!("lifo={}", if lifo_slot { "on" } else { "off" }));
+        }
+        if let Some(max_blocking_threads) = self.max_blocking_threads {
+            options.push(format!("max_blocking_threads={max_blocking_threads}"));
+        }
+
+        write!(f, "{}", options.join(","))
+    }
+}
+
 pub struct TokioExecutor {
     join_handles: Vec<::tokio::task::JoinHandle<()>>,
     runtime: ::tokio::runtime::Runtime,
@@ -26,9 +152,18 @@ impl Executor for TokioExecutor {
 
 impl Default for TokioExecutor {
     fn default() -> Self {
-        let runtime = ::tokio::runtime::Builder::new_multi_thread()
-            .build()
-            .unwrap();
+        let mut builder = ::tokio::runtime::Builder::new_multi_thread();
+        if let Some(cores) = affinity::pinned_cores() {
+            let next_core = AtomicUsize::new(0);
+            builder
+                .worker_threads(cores.len())
+                .on_thread_start(move || {
+                    let i = next_core.fetch_add(1, Ordering::Relaxed);
+                    affinity::pin_current_thread(cores[i % cores.len()]);
+                });
+        }
+        TokioOptions::apply(&mut builder);
+        let runtime = builder.build().unwrap();
 
         Self {
             join_handles: Vec::new(),
@@ -37,29 +172,170 @@ impl Default for TokioExecutor {
     }
 }
 
-#[cfg(feature = "smol")]
-static SMOL_EXECUTOR: ::smol::Executor<'static> = ::smol::Executor::new();
+/// A thread-per-core executor made of one single-threaded Tokio runtime per
+/// logical thread.
+///
+/// Tasks are assigned to the runtimes in a round-robin fashion and never
+/// migrate, so most wake-ups are cross-thread wake-ups.
+pub struct TokioTpcExecutor {
+    workers: Vec<TokioTpcWorker>,
+    next_worker: usize,
+    join_handles: Vec<::tokio::task::JoinHandle<()>>,
+}
+
+struct TokioTpcWorker {
+    handle: ::tokio::runtime::Handle,
+    stop_sender: Option<::tokio::sync::oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Executor for TokioTpcExecutor {
+    fn spawn<T: Future<Output = ()> + Send + 'static>(&mut self, future: T) {
+        let worker = &self.workers[self.next_worker];
+        self.next_worker = (self.next_worker + 1) % self.workers.len();
+        self.join_handles.push(worker.handle.spawn(future));
+    }
+    fn join_all(&mut self) {
+        let join_handles = std::mem::take(&mut self.join_handles);
+
+        // The calling thread only waits for the tasks to complete.
+        self.workers[0].handle.block_on(async move {
+            for fut in join_handles {
+                fut.await.unwrap();
+            }
+        });
+    }
+}
+
+impl Default for TokioTpcExecutor {
+    fn default() -> Self {
+        let cores = affinity::pinned_cores();
+        let worker_count = cores.map_or_else(::num_cpus::get, <[usize]>::len);
+        let workers = (0..worker_count)
+            .map(|i| {
+                let (handle_sender, handle_receiver) = std::sync::mpsc::channel();
+                let (stop_sender, stop_receiver) = ::tokio::sync::oneshot::channel::<()>();
+                let thread = std::thread::spawn(move || {
+                    if let Some(cores) = cores {
+                        affinity::pin_current_thread(cores[i]);
+                    }
+                    let mut builder = ::tokio::runtime::Builder::new_current_thread();
+                    TokioOptions::apply(&mut builder);
+                    let runtime = builder.build().unwrap();
+                    handle_sender.send(runtime.handle().clone()).unwrap();
 
+                    // Run spawned tasks until the executor is dropped.
+                    runtime.block_on(async move {
+                        let _ = stop_receiver.await;
+                    });
+                });
+
+                TokioTpcWorker {
+                    handle: handle_receiver.recv().unwrap(),
+                    stop_sender: Some(stop_sender),
+                    thread: Some(thread),
+                }
+            })
+            .collect();
+
+        Self {
+            workers,
+            next_worker: 0,
+            join_handles: Vec::new(),
+        }
+    }
+}
+
+impl Drop for TokioTpcExecutor {
+    fn drop(&mut self) {
+        for worker in &mut self.workers {
+            worker.stop_sender = None;
+        }
+        for worker in &mut self.workers {
+            worker.thread.take().unwrap().join().unwrap();
+        }
+    }
+}
+
+/// A multi-threaded smol executor.
+///
+/// Tasks are run by runner threads as well as by the thread calling
+/// `join_all`.
 #[cfg(feature = "smol")]
-#[derive(Default)]
 pub struct SmolExecutor {
+    executor: Arc<::smol::Executor<'static>>,
     join_handles: Vec<::smol::Task<()>>,
+    stop_sender: Option<::async_channel::Sender<()>>,
+    runners: Vec<JoinHandle<()>>,
 }
 #[cfg(feature = "smol")]
 impl Executor for SmolExecutor {
     fn spawn<T: Future<Output = ()> + Send + 'static>(&mut self, future: T) {
-        self.join_handles.push(SMOL_EXECUTOR.spawn(future));
+        self.join_handles.push(self.executor.spawn(future));
     }
     fn join_all(&mut self) {
         let join_handles = std::mem::take(&mut self.join_handles);
-        std::thread::spawn(move || {
-            smol::future::block_on(SMOL_EXECUTOR.run(smol::future::pending::<()>()))
-        });
-        ::smol::future::block_on(async move {
+        ::smol::future::block_on(self.executor.run(async move {
             for fut in join_handles {
                 fut.await;
             }
-        });
+        }));
+    }
+}
+#[cfg(feature = "smol")]
+impl Default for SmolExecutor {
+    fn default() -> Self {
+        let executor = Arc::new(::smol::Executor::new());
+
+        // Runner threads stop when the sender is dropped.
+        let (stop_sender, stop_receiver) = ::async_channel::bounded::<()>(1);
+        let runners = (1..::num_cpus::get())
+            .map(|_| {
+                let executor = executor.clone();
+                let stop_receiver = stop_receiver.clone();
+                std::thread::spawn(move || {
+                    let _ = ::smol::future::block_on(executor.run(stop_receiver.recv()));
+                })
+            })
+            .collect();
+
+        Self {
+            executor,
+            join_handles: Vec::new(),
+            stop_sender: Some(stop_sender),
+            runners,
+        }
+    }
+}
+#[cfg(feature = "smol")]
+impl Drop for SmolExecutor {
+    fn drop(&mut self) {
+        self.stop_sender = None;
+        for runner in self.runners.drain(..) {
+            runner.join().unwrap();
+        }
+    }
+}
+
+/// A single-threaded smol executor.
+#[cfg(feature = "smol")]
+#[derive(Default)]
+pub struct SmolLocalExecutor {
+    executor: ::smol::LocalExecutor<'static>,
+    join_handles: Vec<::smol::Task<()>>,
+}
+#[cfg(feature = "smol")]
+impl Executor for SmolLocalExecutor {
+    fn spawn<T: Future<Output = ()> + Send + 'static>(&mut self, future: T) {
+        self.join_handles.push(self.executor.spawn(future));
+    }
+    fn join_all(&mut self) {
+        let join_handles = std::mem::take(&mut self.join_handles);
+        ::smol::future::block_on(self.executor.run(async move {
+            for fut in join_handles {
+                fut.await;
+            }
+        }));
     }
 }
 
@@ -70,6 +346,9 @@ pub struct SmolScaleExecutor {
 }
 #[cfg(feature = "smolscale")]
 impl Executor for SmolScaleExecutor {
+    // smolscale runs all tasks on a global thread pool.
+    const OWNS_THREADS: bool = false;
+
     fn spawn<T: Future<Output = ()> + Send + 'static>(&mut self, future: T) {
         self.join_handles.push(::smolscale::spawn(future));
     }
@@ -97,8 +376,18 @@ impl Executor for NexosimExecutor {
 
 impl Default for NexosimExecutor {
     fn default() -> Self {
-        Self {
-            executor: ::nexosim::dev_hooks::Executor::new(::num_cpus::get()),
-        }
+        let Some(cores) = affinity::pinned_cores() else {
+            return Self {
+                executor: ::nexosim::dev_hooks::Executor::new(::num_cpus::get()),
+            };
+        };
+
+        // The workers are pinned once started since nexosim does not provide
+        // a thread start hook.
+        let previous_threads = affinity::thread_ids();
+        let executor = ::nexosim::dev_hooks::Executor::new(cores.len());
+        affinity::pin_new_threads(&previous_threads, cores);
+
+        Self { executor }
     }
 }
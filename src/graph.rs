@@ -0,0 +1,244 @@
+//! Graphs over which the visitors of the pinball benchmarks perform their
+//! random walk.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A directed graph with weighted edges.
+///
+/// The weight of an edge is proportional to the probability that a visitor
+/// is forwarded along this edge.
+#[derive(Clone)]
+pub struct Graph {
+    /// The weight of the edge from node `i` to node `j` is `weights[i][j]`,
+    /// or 0 if there is no such edge.
+    weights: Vec<Vec<f64>>,
+    /// Whether all edges between distinct nodes have the same weight.
+    uniform: bool,
+}
+
+impl Graph {
+    /// A complete graph with uniform weights.
+    pub fn complete(node_count: usize) -> Self {
+        Self::from_fn(node_count, |_, _| 1.0)
+    }
+
+    /// A complete graph where the probability of forwarding a visitor to the
+    /// node of rank `k` follows Zipf's law with exponent 1, node 0 being the
+    /// busiest.
+    pub fn zipf(node_count: usize) -> Self {
+        Self::from_fn(node_count, |_, j| 1.0 / (j + 1) as f64)
+    }
+
+    /// A ring where each node forwards visitors to the next node.
+    pub fn ring(node_count: usize) -> Self {
+        Self::from_fn(node_count, |i, j| {
+            if j == (i + 1) % node_count {
+                1.0
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// A star where node 0 is the hub, which forwards visitors to randomly
+    /// chosen leaves while leaves only forward visitors to the hub.
+    pub fn star(node_count: usize) -> Self {
+        Self::from_fn(node_count, |i, j| if i == 0 || j == 0 { 1.0 } else { 0.0 })
+    }
+
+    /// Loads a graph from an edge list file.
+    ///
+    /// Each line describes an edge from a source node to a destination node
+    /// with an optional weight, which defaults to 1, either as
+    /// whitespace-separated fields (`src dst [weight]`) or in DOT syntax
+    /// (`src -> dst [weight=w];`). Undirected DOT edges (`a -- b`) are
+    /// converted to two directed edges, while edge chains (`a -> b -> c`) are
+    /// rejected. Nodes are identified by arbitrary names and may also be
+    /// declared by DOT node statements. Empty lines, comments starting with `#`
+    /// or `//`, and the opening and closing lines of a DOT graph are ignored.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read graph file <{}>: {e}", path.display()))?;
+
+        Self::parse(&text).map_err(|e| format!("Invalid graph file <{}>: {e}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut node_ids = HashMap::new();
+        let mut edges = Vec::new();
+
+        for (line_id, line) in text.lines().enumerate() {
+            let line_number = line_id + 1;
+            let line = line
+                .split("//")
+                .next()
+                .unwrap()
+                .trim()
+                .trim_end_matches(';');
+            if line.is_empty() || line.starts_with('#') || line.ends_with('{') || line == "}" {
+                continue;
+            }
+
+            let (edge, attributes) = match line.split_once('[') {
+                Some((edge, attributes)) => (edge, Some(attributes.trim_end_matches(']'))),
+                None => (line, None),
+            };
+            let undirected = edge.contains("--");
+            if edge.matches("->").count() + edge.matches("--").count() > 1 {
+                return Err(format!(
+                    "line {line_number}: edge chains are not supported, \
+                     use one statement per edge"
+                ));
+            }
+            let edge = edge.replace("->", " ").replace("--", " ");
+            let fields: Vec<&str> = edge.split_whitespace().collect();
+
+            let mut node_id = |name: &str| {
+                let next_id = node_ids.len();
+                *node_ids.entry(name.to_string()).or_insert(next_id)
+            };
+
+            // Register the nodes of DOT node statements, which must still
+            // have an outgoing edge declared elsewhere.
+            if let [name] = fields[..] {
+                node_id(name);
+                continue;
+            }
+
+            let weight: f64 = match (&fields[..], attributes) {
+                ([_, _], None) => Ok(1.0),
+                ([_, _, weight], None) => weight.parse(),
+                ([_, _], Some(attributes)) => attributes
+                    .split(',')
+                    .filter_map(|attribute| attribute.trim().strip_prefix("weight="))
+                    .map(|weight| weight.trim_matches('"').parse())
+                    .next()
+                    .unwrap_or(Ok(1.0)),
+                _ => return Err(format!("line {line_number}: expected an edge")),
+            }
+            .map_err(|_| format!("line {line_number}: invalid weight"))?;
+            if !(weight.is_finite() && weight > 0.0) {
+                return Err(format!("line {line_number}: weight must be positive"));
+            }
+
+            let (src, dst) = (node_id(fields[0]), node_id(fields[1]));
+            if src == dst {
+                return Err(format!("line {line_number}: self-loops are not supported"));
+            }
+            edges.push((src, dst, weight));
+            if undirected {
+                edges.push((dst, src, weight));
+            }
+        }
+
+        let node_count = node_ids.len();
+        if node_count < 2 {
+            return Err(String::from("the graph must have at least 2 nodes"));
+        }
+        let mut weights = vec![vec![0.0; node_count]; node_count];
+        for (src, dst, weight) in edges {
+            weights[src][dst] += weight;
+        }
+        if let Some((name, _)) = node_ids
+            .iter()
+            .find(|(_, &id)| weights[id].iter().all(|&weight| weight == 0.0))
+        {
+            return Err(format!("node '{name}' has no outgoing edge"));
+        }
+
+        Ok(Self::new(weights))
+    }
+
+    fn from_fn(node_count: usize, weight: impl Fn(usize, usize) -> f64) -> Self {
+        let weights = (0..node_count)
+            .map(|i| {
+                (0..node_count)
+                    .map(|j| if i != j { weight(i, j) } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+
+        Self::new(weights)
+    }
+
+    fn new(weights: Vec<Vec<f64>>) -> Self {
+        let uniform = weights.iter().enumerate().all(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .all(|(j, &weight)| i == j || weight == row[(i + 1) % row.len()])
+        });
+
+        Self { weights, uniform }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Returns the router of the specified node.
+    pub fn router(&self, node_id: usize) -> Router {
+        if self.uniform {
+            return Router::Uniform {
+                node_id,
+                node_count: self.node_count(),
+            };
+        }
+
+        let mut total_weight = 0.0;
+        let (targets, cumulative_weights) = self.weights[node_id]
+            .iter()
+            .enumerate()
+            .filter(|(_, &weight)| weight > 0.0)
+            .map(|(j, &weight)| {
+                total_weight += weight;
+                (j, total_weight)
+            })
+            .unzip();
+
+        Router::Weighted {
+            targets,
+            cumulative_weights,
+        }
+    }
+}
+
+/// Randomly selects the next node of a walk.
+pub enum Router {
+    /// All other nodes are equally likely.
+    Uniform { node_id: usize, node_count: usize },
+    /// The probability of each target is proportional to the weight of its
+    /// edge.
+    Weighted {
+        targets: Vec<usize>,
+        cumulative_weights: Vec<f64>,
+    },
+}
+
+impl Router {
+    pub fn next(&self, rng: &mut oorandom::Rand64) -> usize {
+        match self {
+            Router::Uniform {
+                node_id,
+                node_count,
+            } => {
+                let j = rng.rand_range(0..(*node_count - 1) as u64) as usize;
+                if j < *node_id {
+                    j
+                } else {
+                    j + 1
+                }
+            }
+            Router::Weighted {
+                targets,
+                cumulative_weights,
+            } => {
+                let x = rng.rand_float() * cumulative_weights.last().unwrap();
+                let k = cumulative_weights.partition_point(|&w| w <= x);
+
+                targets[k.min(targets.len() - 1)]
+            }
+        }
+    }
+}
@@ -11,3 +11,34 @@ macro_rules! add_bench {
 }
 
 pub(crate) use add_bench;
+
+macro_rules! add_oneshot_bench {
+    () => {
+        bench!(async_channel);
+        bench!(futures_oneshot);
+        bench!(oneshot);
+        bench!(tokio_oneshot);
+    };
+}
+
+pub(crate) use add_oneshot_bench;
+
+macro_rules! add_watch_bench {
+    () => {
+        bench!(postage_watch);
+        bench!(tokio_watch);
+    };
+}
+
+pub(crate) use add_watch_bench;
+
+macro_rules! add_unbounded_bench {
+    () => {
+        bench!(async_channel);
+        bench!(flume);
+        bench!(futures_mpsc);
+        bench!(tokio_mpsc);
+    };
+}
+
+pub(crate) use add_unbounded_bench;
@@ -1,15 +1,31 @@
 use std::collections::BTreeMap;
 use std::ffi::OsString;
+use std::fmt;
 use std::fs::File;
 use std::io::Write;
 use std::num::NonZeroU32;
+use std::path::Path;
+use std::time::Duration;
 
 use lexopt::prelude::*;
 
+use crate::watchdog::{BenchThread, TimedOut, Watched};
+
+mod affinity;
 mod benches;
 mod channel_shims;
+mod conformance;
 mod executor_shims;
+mod graph;
 mod macros;
+mod oneshot_shims;
+mod payload;
+mod rusage;
+mod soak;
+mod utils;
+mod validation;
+mod watch_shims;
+mod watchdog;
 
 const HELP_MESSAGE: &str = "\
 tachyobench
@@ -17,21 +33,64 @@ Benchmark runner for Tachyonix
 
 USAGE:
     tachyobench [OPTIONS] <BENCHNAME>
+    tachyobench [OPTIONS] soak <CHANNEL>
 
 ARGS:
     <BENCHNAME>    If specified, only run benches containing this string in their names
+    <CHANNEL>      If specified, only soak test channels containing this string in
+                   their names
 
 OPTIONS:
     -h, --help             Print help information
     -l, --list             List available benches
+    -c, --conformance      Check the close and disconnection semantics of all
+                           MPSC channels instead of running benches
     -s, --samples SAMPLES  Repeat benches SAMPLES times and average the result
     -o, --output FILE      Save the results to FILE
+        --graph FILE       Replace the complete graph of the pinball benches
+                           (except pinball_routing) by the graph described by
+                           the edge list in FILE
+        --seed SEED        Seed the random walks of the pinball benches and the soak
+                           test with SEED, or with a different seed for each
+                           sample if SEED is 'random' [default: 0]
+    -p, --pin-threads[=CORES]
+                           Pin each worker thread of the tokio, tokio_tpc and
+                           nexosim runtimes to a distinct core, using one
+                           worker per core in CORES (e.g. '0,2,4-7') or per
+                           core available to the process if CORES is omitted
+    -t, --timeout SECONDS  Give up a bench if one of its samples lasts more than
+                           SECONDS, report its remaining results as timed out
+                           and skip all subsequent benches
+        --keep-going       Run the subsequent benches after a bench was given
+                           up on, even though it keeps running in the
+                           background and skews their results
+    -v, --validate         Check that messages are neither lost, duplicated nor
+                           reordered and fail on violation; only applies to
+                           the funnel and pinball benches and their unbounded
+                           and routing variants
+    -w, --work NANOS       Spin for NANOS nanoseconds on each message received
+                           by the funnel and pinball benches and their
+                           variants [default: 0]
     -e, --exec EXECUTOR    Run the bench with the EXECUTOR runtime;
                            possible values:
                                tokio [default],
+                               tokio_tpc,
                                nexosim,
                                smol [requires feature 'smol'],
-                               smolscale [requires feature 'smolscale']";
+                               smol_local [requires feature 'smol'],
+                               smolscale [requires feature 'smolscale']
+                           The tokio and tokio_tpc runtimes accept
+                           comma-separated options after a colon, e.g.
+                           'tokio:lifo=off,global_queue_interval=31':
+                               event_interval=N,
+                               global_queue_interval=N,
+                               lifo=on|off [off requires '--cfg tokio_unstable'],
+                               max_blocking_threads=N
+
+SOAK OPTIONS:
+    -d, --duration SECONDS       Stop the soak test after SECONDS [default: never]
+        --stall-timeout SECONDS  Abort and list the blocked tasks if no message is
+                                 transferred within SECONDS [default: 10]";
 
 macro_rules! add_test {
     ($group:ident, $channel:ident) => {
@@ -41,21 +100,30 @@ macro_rules! add_test {
             &[
                 (
                     ExecutorId::Tokio,
-                    benches::$group::$channel::bench::<crate::executor_shims::TokioExecutor>,
+                    benches::$group::$channel::bench::<Watched<executor_shims::TokioExecutor>>,
                 ),
                 #[cfg(feature = "smol")]
                 (
                     ExecutorId::Smol,
-                    benches::$group::$channel::bench::<crate::executor_shims::SmolExecutor>,
+                    benches::$group::$channel::bench::<Watched<executor_shims::SmolExecutor>>,
+                ),
+                #[cfg(feature = "smol")]
+                (
+                    ExecutorId::SmolLocal,
+                    benches::$group::$channel::bench::<Watched<executor_shims::SmolLocalExecutor>>,
                 ),
                 #[cfg(feature = "smolscale")]
                 (
                     ExecutorId::SmolScale,
-                    benches::$group::$channel::bench::<crate::executor_shims::SmolScaleExecutor>,
+                    benches::$group::$channel::bench::<Watched<executor_shims::SmolScaleExecutor>>,
+                ),
+                (
+                    ExecutorId::TokioTpc,
+                    benches::$group::$channel::bench::<Watched<executor_shims::TokioTpcExecutor>>,
                 ),
                 (
                     ExecutorId::Nexosim,
-                    benches::$group::$channel::bench::<crate::executor_shims::NexosimExecutor>,
+                    benches::$group::$channel::bench::<Watched<executor_shims::NexosimExecutor>>,
                 ),
             ],
         )
@@ -63,7 +131,28 @@ macro_rules! add_test {
 }
 
 #[allow(clippy::type_complexity)]
-const BENCHES: &[(&str, &str, &[(ExecutorId, fn(NonZeroU32) -> BenchIterator)])] = &[
+const BENCHES: &[(
+    &str,
+    &str,
+    &[(ExecutorId, fn(BenchConfig) -> BenchIterator)],
+)] = &[
+    add_test!(backpressure, async_channel),
+    add_test!(backpressure, flume),
+    add_test!(backpressure, futures_mpsc),
+    add_test!(backpressure, tachyonix),
+    add_test!(backpressure, thingbuf),
+    add_test!(backpressure, postage_mpsc),
+    add_test!(backpressure, tokio_mpsc),
+    add_test!(burst, async_channel),
+    add_test!(burst, flume),
+    add_test!(burst, futures_mpsc),
+    add_test!(burst, tachyonix),
+    add_test!(burst, thingbuf),
+    add_test!(burst, postage_mpsc),
+    add_test!(burst, tokio_mpsc),
+    add_test!(executor, fan_out),
+    add_test!(executor, join),
+    add_test!(executor, yield_ping),
     add_test!(funnel, async_channel),
     add_test!(funnel, flume),
     add_test!(funnel, futures_mpsc),
@@ -71,6 +160,28 @@ const BENCHES: &[(&str, &str, &[(ExecutorId, fn(NonZeroU32) -> BenchIterator)])]
     add_test!(funnel, thingbuf),
     add_test!(funnel, postage_mpsc),
     add_test!(funnel, tokio_mpsc),
+    add_test!(funnel_payload, async_channel),
+    add_test!(funnel_payload, flume),
+    add_test!(funnel_payload, futures_mpsc),
+    add_test!(funnel_payload, tachyonix),
+    add_test!(funnel_payload, thingbuf),
+    add_test!(funnel_payload, postage_mpsc),
+    add_test!(funnel_payload, tokio_mpsc),
+    add_test!(funnel_try, async_channel),
+    add_test!(funnel_try, flume),
+    add_test!(funnel_try, futures_mpsc),
+    add_test!(funnel_try, tachyonix),
+    add_test!(funnel_try, thingbuf),
+    add_test!(funnel_try, postage_mpsc),
+    add_test!(funnel_try, tokio_mpsc),
+    add_test!(funnel_unbounded, async_channel),
+    add_test!(funnel_unbounded, flume),
+    add_test!(funnel_unbounded, futures_mpsc),
+    add_test!(funnel_unbounded, tokio_mpsc),
+    add_test!(oneshot, async_channel),
+    add_test!(oneshot, futures_oneshot),
+    add_test!(oneshot, oneshot),
+    add_test!(oneshot, tokio_oneshot),
     add_test!(pinball, async_channel),
     add_test!(pinball, flume),
     add_test!(pinball, futures_mpsc),
@@ -78,12 +189,86 @@ const BENCHES: &[(&str, &str, &[(ExecutorId, fn(NonZeroU32) -> BenchIterator)])]
     add_test!(pinball, thingbuf),
     add_test!(pinball, postage_mpsc),
     add_test!(pinball, tokio_mpsc),
+    add_test!(pinball_payload, async_channel),
+    add_test!(pinball_payload, flume),
+    add_test!(pinball_payload, futures_mpsc),
+    add_test!(pinball_payload, tachyonix),
+    add_test!(pinball_payload, thingbuf),
+    add_test!(pinball_payload, postage_mpsc),
+    add_test!(pinball_payload, tokio_mpsc),
+    add_test!(pinball_routing, async_channel),
+    add_test!(pinball_routing, flume),
+    add_test!(pinball_routing, futures_mpsc),
+    add_test!(pinball_routing, tachyonix),
+    add_test!(pinball_routing, thingbuf),
+    add_test!(pinball_routing, postage_mpsc),
+    add_test!(pinball_routing, tokio_mpsc),
+    add_test!(pinball_unbounded, async_channel),
+    add_test!(pinball_unbounded, flume),
+    add_test!(pinball_unbounded, futures_mpsc),
+    add_test!(pinball_unbounded, tokio_mpsc),
+    add_test!(rpc, async_channel),
+    add_test!(rpc, flume),
+    add_test!(rpc, futures_mpsc),
+    add_test!(rpc, tachyonix),
+    add_test!(rpc, thingbuf),
+    add_test!(rpc, postage_mpsc),
+    add_test!(rpc, tokio_mpsc),
+    add_test!(teardown, async_channel),
+    add_test!(teardown, flume),
+    add_test!(teardown, futures_mpsc),
+    add_test!(teardown, tachyonix),
+    add_test!(teardown, thingbuf),
+    add_test!(teardown, postage_mpsc),
+    add_test!(teardown, tokio_mpsc),
+    add_test!(watch, postage_watch),
+    add_test!(watch, tokio_watch),
 ];
 
+/// Options that apply to all benches.
+#[derive(Clone)]
+pub struct BenchConfig {
+    pub samples: NonZeroU32,
+    pub validate: bool,
+    pub seed: Seed,
+    pub graph: Option<graph::Graph>,
+    /// Synthetic work per received message in the funnel and pinball
+    /// benches, in nanoseconds.
+    pub work: u64,
+}
+
+/// Seed of the pseudo-random number generators used by benches.
+#[derive(Clone, Copy)]
+pub enum Seed {
+    /// The same seed is used for all samples.
+    Fixed(u64),
+    /// Each sample uses the base seed incremented by the sample index.
+    PerSample(u64),
+}
+impl Seed {
+    pub fn sample_seed(&self, sample: u32) -> u64 {
+        match *self {
+            Seed::Fixed(seed) => seed,
+            Seed::PerSample(seed) => seed.wrapping_add(sample as u64),
+        }
+    }
+}
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Seed::Fixed(seed) => write!(f, "{seed}"),
+            Seed::PerSample(seed) => write!(f, "{seed} + sample index"),
+        }
+    }
+}
+
 pub struct BenchResult {
     label: String,
     parameter: String,
     throughput: Vec<f64>,
+    /// The unit of what is counted by the throughput, such as `msg`.
+    unit: &'static str,
+    metrics: Vec<Metric>,
 }
 impl BenchResult {
     pub fn new(label: String, parameter: String, throughput: Vec<f64>) -> Self {
@@ -91,8 +276,41 @@ impl BenchResult {
             label,
             parameter,
             throughput,
+            unit: "msg",
+            metrics: Vec::new(),
         }
     }
+
+    /// Sets the unit of what is counted by the throughput, which is `msg` by
+    /// default.
+    pub fn with_unit(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+
+        self
+    }
+
+    /// Attaches a secondary metric, which is reported below the throughput.
+    pub fn with_metric(
+        mut self,
+        name: &'static str,
+        unit: &'static str,
+        samples: Vec<f64>,
+    ) -> Self {
+        self.metrics.push(Metric {
+            name,
+            unit,
+            samples,
+        });
+
+        self
+    }
+}
+
+/// A secondary metric measured alongside the throughput.
+pub struct Metric {
+    name: &'static str,
+    unit: &'static str,
+    samples: Vec<f64>,
 }
 
 type BenchIterator = Box<dyn Iterator<Item = BenchResult>>;
@@ -100,37 +318,55 @@ type BenchIterator = Box<dyn Iterator<Item = BenchResult>>;
 #[derive(PartialEq)]
 enum ExecutorId {
     Tokio,
+    TokioTpc,
     Nexosim,
     #[cfg(feature = "smol")]
     Smol,
+    #[cfg(feature = "smol")]
+    SmolLocal,
     #[cfg(feature = "smolscale")]
     SmolScale,
 }
 impl ExecutorId {
     const TOKIO: &'static str = "tokio";
+    const TOKIO_TPC: &'static str = "tokio_tpc";
     const NEXOSIM: &'static str = "nexosim";
     #[cfg(feature = "smol")]
     const SMOL: &'static str = "smol";
+    #[cfg(feature = "smol")]
+    const SMOL_LOCAL: &'static str = "smol_local";
     #[cfg(feature = "smolscale")]
     const SMOLSCALE: &'static str = "smolscale";
 
     fn new(name: &str) -> Result<Self, ()> {
         match name {
             Self::TOKIO => Ok(ExecutorId::Tokio),
+            Self::TOKIO_TPC => Ok(ExecutorId::TokioTpc),
             Self::NEXOSIM => Ok(ExecutorId::Nexosim),
             #[cfg(feature = "smol")]
             Self::SMOL => Ok(ExecutorId::Smol),
+            #[cfg(feature = "smol")]
+            Self::SMOL_LOCAL => Ok(ExecutorId::SmolLocal),
             #[cfg(feature = "smolscale")]
             Self::SMOLSCALE => Ok(ExecutorId::SmolScale),
             _ => Err(()),
         }
     }
+    fn supports_pinning(&self) -> bool {
+        matches!(
+            self,
+            ExecutorId::Tokio | ExecutorId::TokioTpc | ExecutorId::Nexosim
+        )
+    }
     fn name(&self) -> &'static str {
         match self {
             ExecutorId::Tokio => Self::TOKIO,
+            ExecutorId::TokioTpc => Self::TOKIO_TPC,
             ExecutorId::Nexosim => Self::NEXOSIM,
             #[cfg(feature = "smol")]
             ExecutorId::Smol => Self::SMOL,
+            #[cfg(feature = "smol")]
+            ExecutorId::SmolLocal => Self::SMOL_LOCAL,
             #[cfg(feature = "smolscale")]
             ExecutorId::SmolScale => Self::SMOLSCALE,
         }
@@ -140,15 +376,35 @@ impl ExecutorId {
 struct BenchArgs {
     bench_substrings: Vec<String>,
     executor: ExecutorId,
+    tokio_options: Option<executor_shims::TokioOptions>,
     samples: NonZeroU32,
     output: Option<OsString>,
+    validate: bool,
+    seed: Option<Seed>,
+    graph: Option<OsString>,
+    work: u64,
+    pin_threads: Option<Option<String>>,
+    timeout: Option<Duration>,
+    keep_going: bool,
+    soak: Option<soak::SoakConfig>,
 }
 
 fn parse_args() -> Result<Option<BenchArgs>, lexopt::Error> {
     let mut samples = NonZeroU32::new(1).unwrap();
     let mut executor = ExecutorId::Tokio;
+    let mut tokio_options = None;
     let mut bench_substrings = Vec::new();
     let mut output = None;
+    let mut validate = false;
+    let mut seed = None;
+    let mut graph = None;
+    let mut work = 0;
+    let mut pin_threads = None;
+    let mut timeout = None;
+    let mut keep_going = false;
+    let mut soak = false;
+    let mut duration = None;
+    let mut stall_timeout = Duration::from_secs(10);
 
     let mut parser = lexopt::Parser::from_env();
     while let Some(arg) = parser.next()? {
@@ -165,20 +421,75 @@ fn parse_args() -> Result<Option<BenchArgs>, lexopt::Error> {
 
                 return Ok(None);
             }
+            Short('c') | Long("conformance") => {
+                conformance::run();
+
+                return Ok(None);
+            }
             Short('s') | Long("samples") => {
                 samples = parser.value()?.parse()?;
             }
             Short('o') | Long("output") => {
                 output = Some(parser.value()?);
             }
+            Long("graph") => {
+                graph = Some(parser.value()?);
+            }
+            Short('w') | Long("work") => {
+                work = parser.value()?.parse()?;
+            }
+            Short('p') | Long("pin-threads") => {
+                pin_threads = Some(
+                    parser
+                        .optional_value()
+                        .map(|cores| cores.into_string())
+                        .transpose()?,
+                );
+            }
+            Long("seed") => {
+                let val = parser.value()?;
+                seed = Some(if val == "random" {
+                    Seed::PerSample(utils::random_seed())
+                } else {
+                    Seed::Fixed(val.parse()?)
+                });
+            }
+            Short('t') | Long("timeout") => {
+                timeout = Some(Duration::from_secs_f64(parser.value()?.parse()?));
+            }
+            Long("keep-going") => {
+                keep_going = true;
+            }
+            Short('v') | Long("validate") => {
+                validate = true;
+            }
             Short('e') | Long("exec") => {
                 let val = parser.value()?;
-                executor = ExecutorId::new(val.clone().into_string()?.as_ref()).map_err(|_| {
-                    lexopt::Error::UnexpectedValue {
-                        option: "exec".into(),
-                        value: val,
-                    }
+                let string = val.clone().into_string()?;
+                let (name, options) = match string.split_once(':') {
+                    Some((name, options)) => (name, Some(options)),
+                    None => (string.as_str(), None),
+                };
+                executor = ExecutorId::new(name).map_err(|_| lexopt::Error::UnexpectedValue {
+                    option: "exec".into(),
+                    value: val,
                 })?;
+                tokio_options = match options {
+                    Some(_) if !matches!(executor, ExecutorId::Tokio | ExecutorId::TokioTpc) => {
+                        return Err(format!("The {name} runtime does not accept options").into());
+                    }
+                    Some(options) => Some(executor_shims::TokioOptions::parse(options)?),
+                    None => None,
+                };
+            }
+            Short('d') | Long("duration") => {
+                duration = Some(Duration::from_secs_f64(parser.value()?.parse()?));
+            }
+            Long("stall-timeout") => {
+                stall_timeout = Duration::from_secs_f64(parser.value()?.parse()?);
+            }
+            Value(val) if val == "soak" && !soak && bench_substrings.is_empty() => {
+                soak = true;
             }
             Value(val) => {
                 bench_substrings.push(val.into_string()?);
@@ -190,8 +501,21 @@ fn parse_args() -> Result<Option<BenchArgs>, lexopt::Error> {
     Ok(Some(BenchArgs {
         bench_substrings,
         executor,
+        tokio_options,
         samples,
         output,
+        validate,
+        seed,
+        graph,
+        work,
+        pin_threads,
+        timeout,
+        keep_going,
+        soak: soak.then_some(soak::SoakConfig {
+            duration,
+            stall_timeout,
+            seed: seed.map(|seed| seed.sample_seed(0)),
+        }),
     }))
 }
 
@@ -199,19 +523,86 @@ fn main() -> Result<(), lexopt::Error> {
     #[allow(clippy::type_complexity)]
     let mut benches: BTreeMap<
         &'static str,
-        BTreeMap<&'static str, fn(NonZeroU32) -> Box<dyn Iterator<Item = BenchResult>>>,
+        BTreeMap<&'static str, fn(BenchConfig) -> Box<dyn Iterator<Item = BenchResult>>>,
     > = BTreeMap::new();
 
     let BenchArgs {
         bench_substrings,
         executor,
+        tokio_options,
         samples,
         output,
+        validate,
+        seed,
+        graph,
+        work,
+        pin_threads,
+        timeout,
+        keep_going,
+        soak,
     } = match parse_args()? {
         None => return Ok(()),
         Some(args) => args,
     };
 
+    // Pin worker threads if requested.
+    let pinned_cores = pin_threads
+        .map(|cores| {
+            if !executor.supports_pinning() {
+                return Err(format!(
+                    "Thread pinning is not supported by the {} runtime",
+                    executor.name()
+                ));
+            }
+            affinity::enable(
+                cores
+                    .as_deref()
+                    .map(affinity::parse_core_list)
+                    .transpose()?,
+            )
+        })
+        .transpose()?
+        .map(|cores| {
+            cores
+                .iter()
+                .map(|core| core.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+
+    // Configure the Tokio runtimes if requested.
+    let tokio_options = tokio_options.map(|options| options.set().to_string());
+
+    // Run the soak test instead of the benches if requested.
+    if let Some(config) = soak {
+        let name = executor.name();
+        match executor {
+            ExecutorId::Tokio => {
+                soak::run::<executor_shims::TokioExecutor>(&bench_substrings, name, config)
+            }
+            ExecutorId::TokioTpc => {
+                soak::run::<executor_shims::TokioTpcExecutor>(&bench_substrings, name, config)
+            }
+            ExecutorId::Nexosim => {
+                soak::run::<executor_shims::NexosimExecutor>(&bench_substrings, name, config)
+            }
+            #[cfg(feature = "smol")]
+            ExecutorId::Smol => {
+                soak::run::<executor_shims::SmolExecutor>(&bench_substrings, name, config)
+            }
+            #[cfg(feature = "smol")]
+            ExecutorId::SmolLocal => {
+                soak::run::<executor_shims::SmolLocalExecutor>(&bench_substrings, name, config)
+            }
+            #[cfg(feature = "smolscale")]
+            ExecutorId::SmolScale => {
+                soak::run::<executor_shims::SmolScaleExecutor>(&bench_substrings, name, config)
+            }
+        }
+
+        return Ok(());
+    }
+
     // Select all requested benches.
     for (group, item, executor_benches) in BENCHES {
         let bench_name = format!("{group}-{item}");
@@ -243,8 +634,47 @@ fn main() -> Result<(), lexopt::Error> {
         })
         .transpose()?;
 
-    // Run sequentially all requested benchmarks.
+    // Load the pinball graph if requested.
+    let graph_name = graph
+        .as_ref()
+        .map(|filename| filename.to_string_lossy().into_owned());
+    let graph = graph
+        .map(|filename| graph::Graph::from_file(Path::new(&filename)))
+        .transpose()?;
+
+    if let Some(file) = &mut output {
+        let mut metadata = vec![("seed", seed.unwrap_or(Seed::Fixed(0)).to_string())];
+        if let (Some(name), Some(graph)) = (&graph_name, &graph) {
+            metadata.push(("graph", format!("{name} ({} nodes)", graph.node_count())));
+        }
+        if work != 0 {
+            metadata.push(("work", format!("{work} ns")));
+        }
+        if let Some(options) = &tokio_options {
+            metadata.push(("tokio options", options.clone()));
+        }
+        if let Some(cores) = &pinned_cores {
+            metadata.push(("pinned cores", cores.clone()));
+        }
+        write_metadata(file, &metadata);
+    }
+
+    let config = BenchConfig {
+        samples,
+        validate,
+        seed: seed.unwrap_or(Seed::Fixed(0)),
+        graph,
+        work,
+    };
+
+    // Run sequentially all requested benchmarks, unless a bench was given up
+    // on and would skew the results of the next ones.
+    let mut abandoned = false;
     for (group, benches) in benches {
+        if abandoned && !keep_going {
+            break;
+        }
+
         println!(
             "Running benchmark '{group}' with the {} runtime.",
             executor.name()
@@ -252,59 +682,143 @@ fn main() -> Result<(), lexopt::Error> {
         if samples.get() != 1 {
             println!("All results are averaged over {samples} runs.");
         }
+        if validate {
+            println!("Message delivery is validated.");
+        }
+        if let Some(seed) = seed {
+            println!("Random number generators are seeded with {seed}.");
+        }
+        if let Some(name) = &graph_name {
+            println!("Pinball graphs are loaded from {name}.");
+        }
+        if work != 0 {
+            println!("Received messages cost {work} ns of synthetic work.");
+        }
+        if let Some(options) = &tokio_options {
+            println!("Tokio runtimes are configured with {options}.");
+        }
+        if let Some(cores) = &pinned_cores {
+            println!("Worker threads are pinned to cores {cores}.");
+        }
 
         // Only used when saving to file.
         let mut column_headers = Vec::new();
+        let mut parameter_label = String::new();
         let mut parameter_column = Vec::new();
         let mut columns = Vec::new();
+        let mut metric_tables: Vec<(&'static str, &'static str, Vec<Vec<String>>)> = Vec::new();
 
         for (bench_id, (name, bench)) in benches.into_iter().enumerate() {
+            if abandoned && !keep_going {
+                // Skipped benches are saved like timed out results.
+                println!("    {name}: skipped");
+                if output.is_some() {
+                    columns.push(Vec::new());
+                    column_headers.push(String::from(name));
+                }
+                continue;
+            }
             println!("    {name}:");
             let mut data_column = Vec::new();
+            let mut bench_thread = BenchThread::spawn(bench, config.clone());
 
-            for (
-                parameter_id,
-                BenchResult {
+            for parameter_id in 0.. {
+                let BenchResult {
                     label,
                     parameter,
                     throughput,
-                },
-            ) in bench(samples).enumerate()
-            {
+                    unit: throughput_unit,
+                    metrics,
+                } = match bench_thread.next(timeout) {
+                    Ok(Some(result)) => result,
+                    Ok(None) => break,
+                    Err(TimedOut) => {
+                        println!(
+                            "        timed out after {} s; remaining results skipped",
+                            timeout.unwrap().as_secs_f64()
+                        );
+                        println!();
+                        if keep_going {
+                            println!(
+                                "WARNING: the bench that timed out keeps running in the \
+                                 background; the results of all subsequent benches are \
+                                 unreliable."
+                            );
+                        } else {
+                            println!(
+                                "WARNING: the bench that timed out keeps running in the \
+                                 background; all subsequent benches are skipped (use \
+                                 --keep-going to run them anyway)."
+                            );
+                        }
+                        abandoned = true;
+                        break;
+                    }
+                };
                 assert!(!throughput.is_empty());
 
-                let mean = throughput.iter().fold(0f64, |acc, s| acc + s) / throughput.len() as f64;
+                let mean_throughput = mean(&throughput);
 
                 if output.is_some() {
-                    if bench_id == 0 && parameter_id == 0 {
-                        column_headers.push(label.clone());
+                    if parameter_column.is_empty() {
+                        parameter_label = label.clone();
                     }
-                    if bench_id == 0 {
+                    if parameter_id == parameter_column.len() {
                         parameter_column.push(parameter.clone());
                     }
-                    data_column.push(format!("{mean:.0}"));
+                    data_column.push(format!("{mean_throughput:.0}"));
+
+                    for (metric_id, metric) in metrics.iter().enumerate() {
+                        if metric_id == metric_tables.len() {
+                            metric_tables.push((metric.name, metric.unit, Vec::new()));
+                        }
+                        let metric_columns = &mut metric_tables[metric_id].2;
+                        metric_columns.resize_with(bench_id + 1, Vec::new);
+                        metric_columns[bench_id].push(format!("{:.3}", mean(&metric.samples)));
+                    }
                 }
 
                 if throughput.len() == 1 {
                     println!(
-                        "        {:<20} {:>12.3} msg/µs",
+                        "        {:<20} {:>12.3} {throughput_unit}/µs",
                         format!("{label}={parameter}"),
-                        mean / 1e6
+                        mean_throughput / 1e6
                     );
                 } else {
-                    let std_dev = (throughput
-                        .iter()
-                        .fold(0f64, |acc, s| acc + (s - mean) * (s - mean))
-                        / throughput.len() as f64)
-                        .sqrt();
+                    let std_dev = std_dev(&throughput, mean_throughput);
 
                     println!(
-                        "        {:<20} {:>12.3} msg/µs [±{:.3}]",
+                        "        {:<20} {:>12.3} {throughput_unit}/µs [±{:.3}]",
                         format!("{label}: {parameter}"),
-                        mean * 1e-6,
+                        mean_throughput * 1e-6,
                         std_dev * 1e-6
                     );
                 }
+
+                for Metric {
+                    name,
+                    unit,
+                    samples,
+                } in &metrics
+                {
+                    assert!(!samples.is_empty());
+
+                    let mean_metric = mean(samples);
+                    if samples.len() == 1 {
+                        println!(
+                            "        {:<20} {:>12.3} {unit}",
+                            format!("  {name}"),
+                            mean_metric
+                        );
+                    } else {
+                        println!(
+                            "        {:<20} {:>12.3} {unit} [±{:.3}]",
+                            format!("  {name}"),
+                            mean_metric,
+                            std_dev(samples, mean_metric)
+                        );
+                    }
+                }
             }
             if output.is_some() {
                 columns.push(data_column);
@@ -315,28 +829,85 @@ fn main() -> Result<(), lexopt::Error> {
 
         // Save to file if requested.
         if let Some(file) = &mut output {
-            columns.insert(0, parameter_column);
-            writeln!(
-                file,
-                "# '{}' benchmark with {} runtime",
-                group,
-                executor.name()
-            )
-            .unwrap();
-            write!(file, "#").unwrap();
-            for header in column_headers {
-                write!(file, "{header:>15} ").unwrap();
-            }
-            writeln!(file).unwrap();
-            for row in 0..columns[0].len() {
-                for column in &columns {
-                    write!(file, " {:>15}", column[row]).unwrap();
-                }
+            if parameter_column.is_empty() {
+                writeln!(
+                    file,
+                    "# '{}' benchmark with {} runtime: timed out before any result",
+                    group,
+                    executor.name()
+                )
+                .unwrap();
                 writeln!(file).unwrap();
+                continue;
+            }
+
+            // Results that timed out or were skipped are saved as NaN.
+            let row_count = parameter_column.len();
+            let pad = |column: &mut Vec<String>| column.resize(row_count, String::from("nan"));
+            columns.iter_mut().for_each(pad);
+            columns.insert(0, parameter_column.clone());
+            column_headers.insert(0, parameter_label);
+            write_table(
+                file,
+                &format!("'{}' benchmark with {} runtime", group, executor.name()),
+                &column_headers,
+                &columns,
+            );
+            for (metric_name, unit, mut metric_columns) in metric_tables {
+                metric_columns.resize_with(columns.len() - 1, Vec::new);
+                metric_columns.iter_mut().for_each(pad);
+                metric_columns.insert(0, parameter_column.clone());
+                write_table(
+                    file,
+                    &format!(
+                        "'{}' benchmark with {} runtime: {} [{}]",
+                        group,
+                        executor.name(),
+                        metric_name,
+                        unit
+                    ),
+                    &column_headers,
+                    &metric_columns,
+                );
             }
-            writeln!(file).unwrap();
         }
     }
 
     Ok(())
 }
+
+fn mean(samples: &[f64]) -> f64 {
+    samples.iter().fold(0f64, |acc, s| acc + s) / samples.len() as f64
+}
+
+fn std_dev(samples: &[f64], mean: f64) -> f64 {
+    (samples
+        .iter()
+        .fold(0f64, |acc, s| acc + (s - mean) * (s - mean))
+        / samples.len() as f64)
+        .sqrt()
+}
+
+/// Writes the options that apply to all benches as a comment block.
+fn write_metadata(file: &mut File, entries: &[(&str, String)]) {
+    for (key, value) in entries {
+        writeln!(file, "# {key}: {value}").unwrap();
+    }
+    writeln!(file).unwrap();
+}
+
+fn write_table(file: &mut File, title: &str, column_headers: &[String], columns: &[Vec<String>]) {
+    writeln!(file, "# {title}").unwrap();
+    write!(file, "#").unwrap();
+    for header in column_headers {
+        write!(file, "{header:>15} ").unwrap();
+    }
+    writeln!(file).unwrap();
+    for row in 0..columns[0].len() {
+        for column in columns {
+            write!(file, " {:>15}", column[row]).unwrap();
+        }
+        writeln!(file).unwrap();
+    }
+    writeln!(file).unwrap();
+}
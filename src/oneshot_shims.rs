@@ -0,0 +1,109 @@
+pub mod async_channel {
+    use ::async_channel as channel;
+
+    pub struct Sender<T> {
+        inner: channel::Sender<T>,
+    }
+    impl<T> Sender<T> {
+        pub fn send(self, message: T) {
+            // Sending on a bounded channel with capacity 1 never blocks when
+            // it is used as a oneshot channel.
+            self.inner.try_send(message).ok().unwrap();
+        }
+    }
+
+    pub struct Receiver<T> {
+        inner: channel::Receiver<T>,
+    }
+    impl<T> Receiver<T> {
+        pub async fn recv(self) -> Option<T> {
+            self.inner.recv().await.ok()
+        }
+    }
+
+    pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::bounded(1);
+        (Sender { inner: s }, Receiver { inner: r })
+    }
+}
+
+pub mod futures_oneshot {
+    use ::futures_channel::oneshot as channel;
+
+    pub struct Sender<T> {
+        inner: channel::Sender<T>,
+    }
+    impl<T> Sender<T> {
+        pub fn send(self, message: T) {
+            self.inner.send(message).ok().unwrap();
+        }
+    }
+
+    pub struct Receiver<T> {
+        inner: channel::Receiver<T>,
+    }
+    impl<T> Receiver<T> {
+        pub async fn recv(self) -> Option<T> {
+            self.inner.await.ok()
+        }
+    }
+
+    pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::channel();
+        (Sender { inner: s }, Receiver { inner: r })
+    }
+}
+
+pub mod oneshot {
+    use ::oneshot as channel;
+
+    pub struct Sender<T> {
+        inner: channel::Sender<T>,
+    }
+    impl<T> Sender<T> {
+        pub fn send(self, message: T) {
+            self.inner.send(message).ok().unwrap();
+        }
+    }
+
+    pub struct Receiver<T> {
+        inner: channel::Receiver<T>,
+    }
+    impl<T> Receiver<T> {
+        pub async fn recv(self) -> Option<T> {
+            self.inner.await.ok()
+        }
+    }
+
+    pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::channel();
+        (Sender { inner: s }, Receiver { inner: r })
+    }
+}
+
+pub mod tokio_oneshot {
+    use ::tokio::sync::oneshot as channel;
+
+    pub struct Sender<T> {
+        inner: channel::Sender<T>,
+    }
+    impl<T> Sender<T> {
+        pub fn send(self, message: T) {
+            self.inner.send(message).ok().unwrap();
+        }
+    }
+
+    pub struct Receiver<T> {
+        inner: channel::Receiver<T>,
+    }
+    impl<T> Receiver<T> {
+        pub async fn recv(self) -> Option<T> {
+            self.inner.await.ok()
+        }
+    }
+
+    pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::channel();
+        (Sender { inner: s }, Receiver { inner: r })
+    }
+}
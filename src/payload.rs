@@ -0,0 +1,176 @@
+use std::fmt::Debug;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A message type that carries a `usize` value.
+///
+/// This makes it possible to run benches with messages of various sizes. The
+/// `Default` and `Clone` bounds are required by some channels to pre-allocate
+/// and recycle their slots.
+pub trait Payload: Debug + Default + Clone + Send + 'static {
+    /// Whether the message can be tagged with its sender and sequence number.
+    const TAGGED: bool = false;
+
+    fn new(value: usize) -> Self;
+    fn value(&self) -> usize;
+    fn set_value(&mut self, value: usize);
+
+    /// Tags the message with its sender ID and sequence number, if supported.
+    fn set_tag(&mut self, _sender_id: usize, _seq: usize) {}
+
+    /// Returns the sender ID and sequence number of a tagged message.
+    fn tag(&self) -> Option<(usize, usize)> {
+        None
+    }
+}
+
+impl Payload for usize {
+    fn new(value: usize) -> Self {
+        value
+    }
+    fn value(&self) -> usize {
+        *self
+    }
+    fn set_value(&mut self, value: usize) {
+        *self = value;
+    }
+}
+
+/// A payload of `N` bytes stored inline.
+///
+/// The value is stored in the first bytes of the array, so `N` should be at
+/// least the size of a `usize`.
+#[derive(Clone, Debug)]
+pub struct Inline<const N: usize>([u8; N]);
+
+impl<const N: usize> Default for Inline<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> Payload for Inline<N> {
+    fn new(value: usize) -> Self {
+        let mut payload = Self::default();
+        payload.set_value(value);
+
+        payload
+    }
+    fn value(&self) -> usize {
+        usize::from_ne_bytes(self.0[..size_of::<usize>()].try_into().unwrap())
+    }
+    fn set_value(&mut self, value: usize) {
+        self.0[..size_of::<usize>()].copy_from_slice(&value.to_ne_bytes());
+    }
+}
+
+/// A heap-allocated payload of `N` bytes.
+///
+/// The default value does not allocate so that channels which reset their
+/// slots to the default value do not perform spurious allocations.
+#[derive(Clone, Debug, Default)]
+pub struct Boxed<const N: usize>(Option<Box<Inline<N>>>);
+
+impl<const N: usize> Payload for Boxed<N> {
+    fn new(value: usize) -> Self {
+        Self(Some(Box::new(Inline::new(value))))
+    }
+    fn value(&self) -> usize {
+        self.0.as_ref().unwrap().value()
+    }
+    fn set_value(&mut self, value: usize) {
+        self.0.as_mut().unwrap().set_value(value);
+    }
+}
+
+/// A `usize` payload tagged with its sender ID and sequence number, used for
+/// message delivery validation.
+#[derive(Clone, Debug, Default)]
+pub struct Tagged {
+    value: usize,
+    sender_id: usize,
+    seq: usize,
+}
+
+impl Payload for Tagged {
+    const TAGGED: bool = true;
+
+    fn new(value: usize) -> Self {
+        Self {
+            value,
+            ..Self::default()
+        }
+    }
+    fn value(&self) -> usize {
+        self.value
+    }
+    fn set_value(&mut self, value: usize) {
+        self.value = value;
+    }
+    fn set_tag(&mut self, sender_id: usize, seq: usize) {
+        self.sender_id = sender_id;
+        self.seq = seq;
+    }
+    fn tag(&self) -> Option<(usize, usize)> {
+        Some((self.sender_id, self.seq))
+    }
+}
+
+/// A message whose drop is recorded by a `DropTracker`.
+///
+/// The default value is not tracked, so that channels which reset their slots
+/// to the default value do not register spurious drops. Cloning a tracked
+/// message makes it be reported as dropped twice.
+#[derive(Clone, Debug, Default)]
+pub struct Counted {
+    inner: Option<(usize, Arc<DropTracker>)>,
+}
+
+impl Counted {
+    pub fn new(id: usize, tracker: &Arc<DropTracker>) -> Self {
+        Self {
+            inner: Some((id, tracker.clone())),
+        }
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        if let Some((id, tracker)) = &self.inner {
+            tracker.record(*id);
+        }
+    }
+}
+
+/// Checks that each `Counted` message is dropped exactly once.
+#[derive(Debug)]
+pub struct DropTracker {
+    dropped: Box<[AtomicBool]>,
+}
+
+impl DropTracker {
+    /// Creates a tracker for messages with IDs in `0..count`.
+    pub fn new(count: usize) -> Arc<Self> {
+        Arc::new(Self {
+            dropped: (0..count).map(|_| AtomicBool::new(false)).collect(),
+        })
+    }
+
+    fn record(&self, id: usize) {
+        assert!(
+            !self.dropped[id].swap(true, Ordering::Relaxed),
+            "message {id} was dropped more than once"
+        );
+    }
+
+    /// Panics if any message with an ID in `0..count` was not dropped.
+    pub fn assert_all_dropped(&self, count: usize) {
+        if let Some(id) = self.dropped[..count]
+            .iter()
+            .position(|dropped| !dropped.load(Ordering::Relaxed))
+        {
+            panic!("message {id} was never dropped");
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Accounting of the CPU time and context switches of the process during
+//! samples.
+//!
+//! Resource usage is only available on Linux. It is measured process-wide, so
+//! it includes all executor worker threads.
+
+use std::ops::Sub;
+use std::time::{Duration, Instant};
+
+use crate::BenchResult;
+
+/// Resource usage of the process.
+#[derive(Clone, Copy)]
+pub struct ResourceUsage {
+    user_time: Duration,
+    system_time: Duration,
+    voluntary_switches: u64,
+    involuntary_switches: u64,
+}
+
+impl ResourceUsage {
+    /// Returns the resource usage of the process since its start, if
+    /// available on this platform.
+    #[cfg(target_os = "linux")]
+    pub fn now() -> Option<Self> {
+        let to_duration = |time: libc::timeval| {
+            Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+        };
+
+        // Safety: `rusage` is plain data and is fully initialized by
+        // `getrusage` on success.
+        let usage = unsafe {
+            let mut usage: libc::rusage = std::mem::zeroed();
+            if libc::getrusage(libc::RUSAGE_SELF, &mut usage) != 0 {
+                return None;
+            }
+            usage
+        };
+
+        Some(Self {
+            user_time: to_duration(usage.ru_utime),
+            system_time: to_duration(usage.ru_stime),
+            voluntary_switches: usage.ru_nvcsw as u64,
+            involuntary_switches: usage.ru_nivcsw as u64,
+        })
+    }
+
+    /// Returns the resource usage of the process since its start, if
+    /// available on this platform.
+    #[cfg(not(target_os = "linux"))]
+    pub fn now() -> Option<Self> {
+        None
+    }
+}
+
+impl Sub for ResourceUsage {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            user_time: self.user_time.saturating_sub(rhs.user_time),
+            system_time: self.system_time.saturating_sub(rhs.system_time),
+            voluntary_switches: self.voluntary_switches - rhs.voluntary_switches,
+            involuntary_switches: self.involuntary_switches - rhs.involuntary_switches,
+        }
+    }
+}
+
+/// The wall time and resource usage of a measured region.
+#[derive(Clone, Copy)]
+pub struct Measurement {
+    wall_time: Duration,
+    usage: ResourceUsage,
+}
+
+/// A measurement in progress.
+pub struct Meter {
+    start_time: Instant,
+    start_usage: Option<ResourceUsage>,
+}
+
+impl Meter {
+    pub fn start() -> Self {
+        Self {
+            start_usage: ResourceUsage::now(),
+            start_time: Instant::now(),
+        }
+    }
+
+    /// Ends the measurement, if resource usage is available.
+    pub fn stop(self) -> Option<Measurement> {
+        let wall_time = self.start_time.elapsed();
+        let (Some(start_usage), Some(usage)) = (self.start_usage, ResourceUsage::now()) else {
+            return None;
+        };
+
+        Some(Measurement {
+            wall_time,
+            usage: usage - start_usage,
+        })
+    }
+}
+
+impl BenchResult {
+    /// Adds the resource usage measured during each sample of a bench that
+    /// transfers messages as metrics.
+    ///
+    /// Nothing is added if any of the measurements is missing.
+    pub fn with_resource_usage(self, measurements: Vec<Option<Measurement>>) -> Self {
+        let Some(samples) = measurements.into_iter().collect::<Option<Vec<_>>>() else {
+            return self;
+        };
+        assert_eq!(samples.len(), self.throughput.len());
+
+        // The CPU time per message is the average number of busy threads
+        // divided by the throughput.
+        let cpu_time: Vec<_> = samples
+            .iter()
+            .zip(&self.throughput)
+            .map(|(sample, throughput)| {
+                let busy_threads = (sample.usage.user_time + sample.usage.system_time)
+                    .as_secs_f64()
+                    / sample.wall_time.as_secs_f64();
+
+                busy_threads / throughput * 1e6
+            })
+            .collect();
+        let metric = |f: fn(&Measurement) -> f64| samples.iter().map(f).collect::<Vec<_>>();
+
+        self.with_metric("CPU time", "s/Mmsg", cpu_time)
+            .with_metric(
+                "user time",
+                "ms",
+                metric(|m| m.usage.user_time.as_secs_f64() * 1e3),
+            )
+            .with_metric(
+                "system time",
+                "ms",
+                metric(|m| m.usage.system_time.as_secs_f64() * 1e3),
+            )
+            .with_metric(
+                "vol. switches",
+                "count",
+                metric(|m| m.usage.voluntary_switches as f64),
+            )
+            .with_metric(
+                "invol. switches",
+                "count",
+                metric(|m| m.usage.involuntary_switches as f64),
+            )
+    }
+}
@@ -0,0 +1,274 @@
+//! Soak testing of channels with the funnel and pinball topologies of the
+//! benches and randomized dimensions.
+//!
+//! Iterations run until the requested duration has elapsed and progress is
+//! reported periodically. A watchdog aborts the process with a list of the
+//! blocked tasks if no message is transferred within the stall timeout, and
+//! message delivery is validated after each iteration. The topology of an
+//! iteration is only printed if it stalls or fails.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::benches::funnel::Funnel;
+use crate::benches::pinball::Pinball;
+use crate::executor_shims::Executor;
+use crate::graph::Graph;
+use crate::validation::Violations;
+use crate::watchdog::{Monitor, Watchdog};
+
+/// Upper bound on the number of messages transferred in an iteration.
+const MAX_MESSAGES: u64 = 200_000;
+
+/// Period of the progress reports.
+const REPORT_PERIOD: Duration = Duration::from_secs(10);
+
+/// Options of the soak test.
+pub struct SoakConfig {
+    pub duration: Option<Duration>,
+    pub stall_timeout: Duration,
+    pub seed: Option<u64>,
+}
+
+/// The randomized topology of an iteration.
+pub enum Topology {
+    Funnel { funnel: Funnel, capacity: usize },
+    Pinball { pinball: Pinball, capacity: usize },
+}
+
+impl Topology {
+    fn random(rng: &mut oorandom::Rand64) -> Self {
+        if rng.rand_range(0..2) == 0 {
+            let channels = rng.rand_range(1..17);
+            let senders_per_channel = rng.rand_range(1..17);
+            let max_messages_per_sender = MAX_MESSAGES / (channels * senders_per_channel);
+
+            Topology::Funnel {
+                funnel: Funnel {
+                    channels: channels as usize,
+                    senders_per_channel: senders_per_channel as usize,
+                    messages_per_sender: rng.rand_range(1..max_messages_per_sender + 1) as usize,
+                },
+                capacity: 1 << rng.rand_range(0..11),
+            }
+        } else {
+            let graph_count = rng.rand_range(1..9);
+            let visitor_count = rng.rand_range(1..65);
+            let max_path_length = MAX_MESSAGES / (graph_count * visitor_count);
+
+            // Senders never block as long as the capacity is at least the
+            // number of visitors.
+            Topology::Pinball {
+                pinball: Pinball {
+                    graph_count: graph_count as usize,
+                    graph: Graph::complete(rng.rand_range(2..17) as usize),
+                    visitor_count: visitor_count as usize,
+                    path_length: rng.rand_range(1..max_path_length + 1) as usize,
+                    seed: rng.rand_u64(),
+                },
+                capacity: rng.rand_range(visitor_count..2 * visitor_count + 1) as usize,
+            }
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topology::Funnel { funnel, capacity } => write!(
+                f,
+                "funnel channels={} senders={} capacity={capacity} messages={}",
+                funnel.channels, funnel.senders_per_channel, funnel.messages_per_sender
+            ),
+            Topology::Pinball { pinball, capacity } => write!(
+                f,
+                "pinball graphs={} nodes={} balls={} capacity={capacity} path={}",
+                pinball.graph_count,
+                pinball.graph.node_count(),
+                pinball.visitor_count,
+                pinball.path_length
+            ),
+        }
+    }
+}
+
+macro_rules! soak {
+    ($channel_name:ident) => {
+        pub mod $channel_name {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            use std::sync::Arc;
+            use std::time::{Duration, Instant};
+
+            use crate::benches::funnel::spawn_funnel;
+            use crate::benches::pinball::spawn_pinball;
+            use crate::channel_shims::$channel_name::channel;
+            use crate::executor_shims::Executor;
+            use crate::payload::{Payload, Tagged};
+            use crate::utils::StartGate;
+            use crate::validation::Violations;
+            use crate::watchdog::Monitor;
+
+            use super::Topology;
+
+            /// Runs an iteration with the specified topology until all tasks
+            /// complete.
+            pub fn run<E: Executor>(
+                topology: &Topology,
+                monitor: &Monitor,
+                violations: &Violations,
+            ) {
+                let mut executor = E::default();
+                let gate = StartGate::default();
+
+                let graph_validators = match topology {
+                    Topology::Funnel { funnel, capacity } => {
+                        spawn_funnel!(
+                            &mut executor,
+                            funnel,
+                            channel(*capacity),
+                            Tagged,
+                            0,
+                            &gate,
+                            violations,
+                            Some(monitor)
+                        );
+
+                        Vec::new()
+                    }
+                    Topology::Pinball { pinball, capacity } => {
+                        spawn_pinball!(
+                            &mut executor,
+                            pinball,
+                            channel(*capacity),
+                            Tagged,
+                            0,
+                            &gate,
+                            violations,
+                            Some(monitor)
+                        )
+                        .1
+                    }
+                };
+
+                gate.open();
+                executor.join_all();
+
+                for graph_validator in graph_validators {
+                    graph_validator.finish(violations);
+                }
+            }
+        }
+    };
+}
+
+soak!(async_channel);
+soak!(flume);
+soak!(futures_mpsc);
+soak!(tachyonix);
+soak!(thingbuf);
+soak!(postage_mpsc);
+soak!(tokio_mpsc);
+
+type IterationFn = fn(&Topology, &Monitor, &Violations);
+
+fn channels<E: Executor>() -> [(&'static str, IterationFn); 7] {
+    [
+        ("async_channel", async_channel::run::<E>),
+        ("flume", flume::run::<E>),
+        ("futures_mpsc", futures_mpsc::run::<E>),
+        ("tachyonix", tachyonix::run::<E>),
+        ("thingbuf", thingbuf::run::<E>),
+        ("postage_mpsc", postage_mpsc::run::<E>),
+        ("tokio_mpsc", tokio_mpsc::run::<E>),
+    ]
+}
+
+/// Runs soak iterations with randomly selected channels among those
+/// containing one of the specified substrings.
+pub fn run<E: Executor>(channel_substrings: &[String], executor_name: &str, config: SoakConfig) {
+    let channels: Vec<_> = channels::<E>()
+        .into_iter()
+        .filter(|(name, _)| {
+            channel_substrings.is_empty()
+                || channel_substrings
+                    .iter()
+                    .any(|substring| name.contains(substring.as_str()))
+        })
+        .collect();
+
+    if channels.is_empty() {
+        println!("No matching channels found");
+
+        return;
+    }
+
+    let seed = config.seed.unwrap_or_else(crate::utils::random_seed);
+    let mut rng = oorandom::Rand64::new(seed as u128);
+
+    println!(
+        "Soak testing {} with the {executor_name} runtime (seed {seed:#x}).",
+        channels
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let start_time = Instant::now();
+    let mut last_report_time = start_time;
+    let mut iterations = 0;
+    let mut operations = 0;
+    while config
+        .duration
+        .is_none_or(|duration| start_time.elapsed() < duration)
+    {
+        iterations += 1;
+        let (channel_name, run_iteration) =
+            channels[rng.rand_range(0..channels.len() as u64) as usize];
+        let topology = Topology::random(&mut rng);
+
+        let description = format!("iteration {iterations}: {channel_name} {topology}");
+
+        let monitor = Monitor::default();
+        let violations = Violations::default();
+        let stall_timeout = config.stall_timeout;
+        let stall_description = description.clone();
+        let watchdog = Watchdog::start(monitor.clone(), stall_timeout, move |monitor| {
+            println!("Stalled {stall_description}");
+            println!(
+                "No message was transferred for {} s; pending tasks:",
+                stall_timeout.as_secs_f64()
+            );
+            for task in monitor.pending_tasks() {
+                println!("        {task}");
+            }
+
+            std::process::exit(1);
+        });
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            run_iteration(&topology, &monitor, &violations);
+            violations.assert_none();
+        }));
+        drop(watchdog);
+        if let Err(payload) = result {
+            println!("Failed {description}");
+            panic::resume_unwind(payload);
+        }
+        operations += monitor.progress();
+
+        if last_report_time.elapsed() >= REPORT_PERIOD {
+            last_report_time = Instant::now();
+            println!(
+                "    {:.0} s: {iterations} iterations, {operations} channel operations",
+                start_time.elapsed().as_secs_f64()
+            );
+        }
+    }
+
+    println!(
+        "Completed {iterations} iterations ({operations} channel operations) without stall \
+         or delivery violation."
+    );
+}
@@ -0,0 +1,136 @@
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::time::{Instant, SystemTime};
+
+/// Yields execution back to the executor once.
+///
+/// This is executor-agnostic: the task simply wakes itself and returns
+/// `Pending` on the first poll.
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false }
+}
+
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.yielded = true;
+        cx.waker().wake_by_ref();
+
+        Poll::Pending
+    }
+}
+
+/// A gate that holds tasks back until it is opened.
+///
+/// Some executors start running tasks as soon as they are spawned while others
+/// only run them from `join_all`. Making all tasks wait for the gate, which is
+/// opened at the start of the timed region, ensures that no task makes progress
+/// before the timed region regardless of the executor.
+#[derive(Clone, Default)]
+pub struct StartGate {
+    inner: Arc<StartGateInner>,
+}
+
+#[derive(Default)]
+struct StartGateInner {
+    is_open: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl StartGate {
+    /// Waits until the gate is opened.
+    pub async fn wait(&self) {
+        future::poll_fn(|cx| {
+            if self.inner.is_open.load(Ordering::Acquire) {
+                return Poll::Ready(());
+            }
+            let mut wakers = self.inner.wakers.lock().unwrap();
+            if self.inner.is_open.load(Ordering::Acquire) {
+                return Poll::Ready(());
+            }
+            wakers.push(cx.waker().clone());
+
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Opens the gate and wakes all waiting tasks.
+    pub fn open(&self) {
+        let wakers = {
+            let mut wakers = self.inner.wakers.lock().unwrap();
+            self.inner.is_open.store(true, Ordering::Release);
+            std::mem::take(&mut *wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// Returns a seed derived from the system time.
+pub fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64
+}
+
+/// Returns the number of threads of the process, if available on this
+/// platform.
+pub fn thread_count() -> Option<usize> {
+    std::fs::read_dir("/proc/self/task")
+        .ok()
+        .map(|tasks| tasks.count())
+}
+
+/// Spins for approximately the specified number of nanoseconds.
+///
+/// The spin loop is calibrated on first use so that the work does not depend
+/// on the cost of reading the clock.
+pub fn spin_for(nanos: u64) {
+    if nanos == 0 {
+        return;
+    }
+    let iterations = (nanos as f64 * spin_iterations_per_nano()) as u64;
+    spin(iterations);
+}
+
+fn spin(iterations: u64) {
+    let mut acc = 0u64;
+    for i in 0..iterations {
+        acc = std::hint::black_box(acc.wrapping_add(i));
+    }
+}
+
+fn spin_iterations_per_nano() -> f64 {
+    static ITERATIONS_PER_NANO: OnceLock<f64> = OnceLock::new();
+
+    *ITERATIONS_PER_NANO.get_or_init(|| {
+        const CALIBRATION_ITERATIONS: u64 = 10_000_000;
+
+        // Warm up, then keep the fastest of a few runs.
+        spin(CALIBRATION_ITERATIONS);
+        let best = (0..5)
+            .map(|_| {
+                let start_time = Instant::now();
+                spin(CALIBRATION_ITERATIONS);
+                Instant::now() - start_time
+            })
+            .min()
+            .unwrap();
+
+        CALIBRATION_ITERATIONS as f64 / best.as_nanos().max(1) as f64
+    })
+}
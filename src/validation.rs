@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of violations kept for reporting.
+const MAX_VIOLATIONS: usize = 10;
+
+/// Violations of the message delivery guarantees reported by bench tasks.
+#[derive(Clone, Default)]
+pub struct Violations(Arc<Mutex<Vec<String>>>);
+
+impl Violations {
+    pub fn report(&self, violation: String) {
+        let mut violations = self.0.lock().unwrap();
+        if violations.len() < MAX_VIOLATIONS {
+            violations.push(violation);
+        }
+    }
+
+    /// Panics with the list of violations, if any.
+    pub fn assert_none(&self) {
+        let violations = self.0.lock().unwrap();
+        if !violations.is_empty() {
+            panic!(
+                "message delivery validation failed:\n    {}",
+                violations.join("\n    ")
+            );
+        }
+    }
+}
+
+/// Checks that the messages of each sender are received exactly once and in
+/// order.
+///
+/// Messages must be tagged with the sender ID and with a per-sender sequence
+/// number starting at 0.
+pub struct SequenceChecker {
+    receiver_id: usize,
+    next_seq: Vec<usize>,
+    violations: Violations,
+}
+
+impl SequenceChecker {
+    pub fn new(receiver_id: usize, sender_count: usize, violations: Violations) -> Self {
+        Self {
+            receiver_id,
+            next_seq: vec![0; sender_count],
+            violations,
+        }
+    }
+
+    pub fn check(&mut self, sender_id: usize, seq: usize) {
+        let expected_seq = self.next_seq[sender_id];
+        if seq != expected_seq {
+            let kind = if seq < expected_seq {
+                "duplicated or reordered"
+            } else {
+                "lost or reordered"
+            };
+            self.violations.report(format!(
+                "receiver {}: got message {} from sender {} instead of message {} ({})",
+                self.receiver_id, seq, sender_id, expected_seq, kind
+            ));
+        }
+        self.next_seq[sender_id] = seq + 1;
+    }
+
+    /// Checks that the number of messages received from each sender matches
+    /// the number of messages sent.
+    pub fn finish(self, sent: &[usize]) {
+        for (sender_id, (&received, &sent)) in self.next_seq.iter().zip(sent).enumerate() {
+            if received != sent {
+                self.violations.report(format!(
+                    "receiver {}: got {} messages from sender {} but {} were sent",
+                    self.receiver_id, received, sender_id, sent
+                ));
+            }
+        }
+    }
+}
+
+/// Collects the sequence checkers of all nodes of a graph along with the
+/// number of messages each node sent to every other node.
+#[derive(Clone)]
+pub struct GraphValidator {
+    nodes: Arc<Mutex<Vec<Option<NodeState>>>>,
+}
+
+/// The final state of a graph node.
+struct NodeState {
+    checker: SequenceChecker,
+    sent: Vec<usize>,
+}
+
+impl GraphValidator {
+    pub fn new(node_count: usize) -> Self {
+        Self {
+            nodes: Arc::new(Mutex::new((0..node_count).map(|_| None).collect())),
+        }
+    }
+
+    /// Records the final state of a node.
+    pub fn complete(&self, node_id: usize, checker: SequenceChecker, sent: Vec<usize>) {
+        self.nodes.lock().unwrap()[node_id] = Some(NodeState { checker, sent });
+    }
+
+    /// Checks that each node received all messages sent to it.
+    ///
+    /// This must be called once all nodes have completed.
+    pub fn finish(self, violations: &Violations) {
+        let nodes = std::mem::take(&mut *self.nodes.lock().unwrap());
+        let sent: Vec<Option<Vec<usize>>> = nodes
+            .iter()
+            .map(|node| node.as_ref().map(|node| node.sent.clone()))
+            .collect();
+
+        for (node_id, node) in nodes.into_iter().enumerate() {
+            let Some(NodeState { checker, .. }) = node else {
+                violations.report(format!("node {node_id} did not complete"));
+                continue;
+            };
+            let sent_to_node: Vec<_> = sent
+                .iter()
+                .map(|sent| sent.as_ref().map_or(0, |sent| sent[node_id]))
+                .collect();
+            checker.finish(&sent_to_node);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+pub mod postage_watch {
+    use ::postage::sink::Sink;
+    use ::postage::stream::Stream;
+    use ::postage::watch as channel;
+
+    pub struct Sender<T> {
+        inner: channel::Sender<T>,
+    }
+    impl<T> Sender<T> {
+        pub async fn send(&mut self, value: T) {
+            self.inner.send(value).await.ok().unwrap();
+        }
+    }
+
+    pub struct Receiver<T> {
+        inner: channel::Receiver<T>,
+    }
+    impl<T> Clone for Receiver<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
+    impl<T: Clone> Receiver<T> {
+        /// Waits for a value that was not yet observed and returns it.
+        ///
+        /// Note that the first call returns the current value immediately.
+        pub async fn changed(&mut self) -> Option<T> {
+            self.inner.recv().await
+        }
+    }
+
+    pub fn channel<T: Clone>(value: T) -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::channel_with(value);
+        (Sender { inner: s }, Receiver { inner: r })
+    }
+}
+
+pub mod tokio_watch {
+    use ::tokio::sync::watch as channel;
+
+    pub struct Sender<T> {
+        inner: channel::Sender<T>,
+    }
+    impl<T> Sender<T> {
+        pub async fn send(&mut self, value: T) {
+            self.inner.send(value).ok().unwrap();
+        }
+    }
+
+    pub struct Receiver<T> {
+        inner: channel::Receiver<T>,
+    }
+    impl<T> Clone for Receiver<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
+    }
+    impl<T: Clone> Receiver<T> {
+        /// Waits for a value that was not yet observed and returns it.
+        pub async fn changed(&mut self) -> Option<T> {
+            self.inner.changed().await.ok()?;
+
+            Some(self.inner.borrow_and_update().clone())
+        }
+    }
+
+    pub fn channel<T>(value: T) -> (Sender<T>, Receiver<T>) {
+        let (s, r) = channel::channel(value);
+        (Sender { inner: s }, Receiver { inner: r })
+    }
+}
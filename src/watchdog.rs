@@ -0,0 +1,368 @@
+//! Progress monitoring of bench tasks and sample timeouts.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::executor_shims::Executor;
+use crate::{BenchConfig, BenchIterator, BenchResult};
+
+/// The state of a monitored task.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+pub enum TaskState {
+    Running = 0,
+    Sending = 1,
+    Receiving = 2,
+    Done = 3,
+}
+
+impl TaskState {
+    fn from_u8(state: u8) -> Self {
+        match state {
+            0 => TaskState::Running,
+            1 => TaskState::Sending,
+            2 => TaskState::Receiving,
+            _ => TaskState::Done,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            TaskState::Running => "running",
+            TaskState::Sending => "blocked on send",
+            TaskState::Receiving => "blocked on receive",
+            TaskState::Done => "done",
+        }
+    }
+}
+
+/// A record of the state of a set of tasks.
+#[derive(Clone, Default)]
+pub struct Monitor {
+    probes: Arc<Mutex<Vec<Arc<ProbeState>>>>,
+}
+
+impl Monitor {
+    /// Registers a new task.
+    pub fn probe(&self, description: String) -> Probe {
+        let state = Arc::new(ProbeState {
+            description,
+            state: AtomicU8::new(TaskState::Running as u8),
+            messages: AtomicUsize::new(0),
+        });
+        self.probes.lock().unwrap().push(state.clone());
+
+        Probe { state: Some(state) }
+    }
+
+    /// Returns the total number of messages transferred by all tasks.
+    pub fn progress(&self) -> usize {
+        self.probes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|probe| probe.messages.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Returns a description of the state of all tasks that have not
+    /// completed.
+    pub fn pending_tasks(&self) -> Vec<String> {
+        self.probes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|probe| {
+                let state = TaskState::from_u8(probe.state.load(Ordering::Relaxed));
+                (state != TaskState::Done).then(|| {
+                    format!(
+                        "{}: {} after {} messages",
+                        probe.description,
+                        state.name(),
+                        probe.messages.load(Ordering::Relaxed)
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+struct ProbeState {
+    description: String,
+    state: AtomicU8,
+    messages: AtomicUsize,
+}
+
+/// A handle used by a task to report its state.
+///
+/// The task is considered completed once the probe is dropped. The default
+/// probe is not registered with any monitor and reports nothing.
+#[derive(Default)]
+pub struct Probe {
+    state: Option<Arc<ProbeState>>,
+}
+
+impl Probe {
+    pub fn set(&self, state: TaskState) {
+        if let Some(probe_state) = &self.state {
+            probe_state.state.store(state as u8, Ordering::Relaxed);
+        }
+    }
+
+    /// Records the transfer of a message and marks the task as running.
+    pub fn advance(&self) {
+        if let Some(probe_state) = &self.state {
+            probe_state.messages.fetch_add(1, Ordering::Relaxed);
+            probe_state
+                .state
+                .store(TaskState::Running as u8, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        self.set(TaskState::Done);
+    }
+}
+
+/// A thread that detects the absence of progress of monitored tasks.
+pub struct Watchdog {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Starts a thread that calls `on_stall` if no message is transferred
+    /// by the monitored tasks within `timeout`.
+    pub fn start<F>(monitor: Monitor, timeout: Duration, on_stall: F) -> Self
+    where
+        F: FnOnce(&Monitor) + Send + 'static,
+    {
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let poll_period = (timeout / 10).max(Duration::from_millis(10));
+
+        let thread = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let (stopped, condvar) = &*stop;
+                let mut last_progress = monitor.progress();
+                let mut last_progress_time = Instant::now();
+                let mut stopped = stopped.lock().unwrap();
+
+                while !*stopped {
+                    stopped = condvar.wait_timeout(stopped, poll_period).unwrap().0;
+
+                    let progress = monitor.progress();
+                    if progress != last_progress {
+                        last_progress = progress;
+                        last_progress_time = Instant::now();
+                    } else if last_progress_time.elapsed() >= timeout && !*stopped {
+                        on_stall(&monitor);
+
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        let (stopped, condvar) = &*self.stop;
+        *stopped.lock().unwrap() = true;
+        condvar.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Error returned when a sample did not complete within the timeout.
+pub struct TimedOut;
+
+/// The start time of the sample being measured by a bench thread.
+#[derive(Default)]
+struct SampleClock {
+    start_time: Mutex<Option<Instant>>,
+    abandoned: AtomicBool,
+}
+
+impl SampleClock {
+    /// Returns the time elapsed since the start of the current sample, if
+    /// any.
+    fn elapsed(&self) -> Option<Duration> {
+        self.start_time
+            .lock()
+            .unwrap()
+            .map(|start_time| start_time.elapsed())
+    }
+}
+
+thread_local! {
+    static SAMPLE_CLOCK: RefCell<Option<Arc<SampleClock>>> = const { RefCell::new(None) };
+}
+
+/// An executor that records the start and end of each sample with the clock
+/// of the bench thread and warns if worker threads outlive the executor.
+pub struct Watched<E: Executor> {
+    executor: Option<E>,
+    thread_count: Option<usize>,
+}
+
+impl<E: Executor> Default for Watched<E> {
+    fn default() -> Self {
+        let thread_count = crate::utils::thread_count();
+
+        Self {
+            executor: Some(E::default()),
+            thread_count,
+        }
+    }
+}
+
+impl<E: Executor> Executor for Watched<E> {
+    const OWNS_THREADS: bool = E::OWNS_THREADS;
+
+    fn spawn<T: Future<Output = ()> + Send + 'static>(&mut self, future: T) {
+        self.executor.as_mut().unwrap().spawn(future);
+    }
+    fn join_all(&mut self) {
+        let clock = SAMPLE_CLOCK.with(|clock| clock.borrow().clone());
+        if let Some(clock) = &clock {
+            *clock.start_time.lock().unwrap() = Some(Instant::now());
+        }
+
+        self.executor.as_mut().unwrap().join_all();
+
+        if let Some(clock) = &clock {
+            *clock.start_time.lock().unwrap() = None;
+
+            // Quietly terminate a bench thread that was given up on rather
+            // than letting it compete with the next benches.
+            if clock.abandoned.load(Ordering::Relaxed) {
+                panic::resume_unwind(Box::new(TimedOut));
+            }
+        }
+    }
+}
+
+impl<E: Executor> Drop for Watched<E> {
+    fn drop(&mut self) {
+        // Skip the check if the bench thread is unwinding, for instance
+        // because it was given up on.
+        if !E::OWNS_THREADS || thread::panicking() {
+            return;
+        }
+        drop(self.executor.take());
+
+        // Exited threads may take a little while to disappear.
+        let Some(initial_count) = self.thread_count else {
+            return;
+        };
+        let mut thread_count = 0;
+        for _ in 0..100 {
+            thread_count = crate::utils::thread_count().unwrap_or(0);
+            if thread_count <= initial_count {
+                return;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        // The count includes all threads of the process, so this may also be
+        // a thread that is unrelated to the executor.
+        eprintln!(
+            "warning: {} thread(s) still running after the executor was dropped",
+            thread_count - initial_count
+        );
+    }
+}
+
+/// A bench running on a dedicated thread, so it can be given up on if a
+/// sample does not complete in time.
+pub struct BenchThread {
+    results: Receiver<BenchResult>,
+    clock: Arc<SampleClock>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BenchThread {
+    pub fn spawn(bench: fn(BenchConfig) -> BenchIterator, config: BenchConfig) -> Self {
+        let (sender, results) = mpsc::channel();
+        let clock = Arc::new(SampleClock::default());
+
+        let thread = thread::spawn({
+            let clock = clock.clone();
+            move || {
+                SAMPLE_CLOCK.with(|c| *c.borrow_mut() = Some(clock));
+                for result in bench(config) {
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            results,
+            clock,
+            thread: Some(thread),
+        }
+    }
+
+    /// Waits for the next result of the bench.
+    ///
+    /// If a sample lasts longer than the timeout, the bench thread is left
+    /// behind and an error is returned. Panics of the bench thread are
+    /// propagated.
+    pub fn next(&mut self, timeout: Option<Duration>) -> Result<Option<BenchResult>, TimedOut> {
+        loop {
+            let result = match timeout {
+                None => self
+                    .results
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+                Some(timeout) => self.results.recv_timeout(
+                    (timeout / 10).clamp(Duration::from_millis(10), Duration::from_secs(1)),
+                ),
+            };
+
+            match result {
+                Ok(result) => return Ok(Some(result)),
+                Err(RecvTimeoutError::Disconnected) => {
+                    if let Some(thread) = self.thread.take() {
+                        if let Err(payload) = thread.join() {
+                            panic::resume_unwind(payload);
+                        }
+                    }
+
+                    return Ok(None);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let timeout = timeout.unwrap();
+                    if self
+                        .clock
+                        .elapsed()
+                        .is_some_and(|elapsed| elapsed >= timeout)
+                    {
+                        self.clock.abandoned.store(true, Ordering::Relaxed);
+
+                        return Err(TimedOut);
+                    }
+                }
+            }
+        }
+    }
+}